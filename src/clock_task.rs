@@ -0,0 +1,109 @@
+// The emulated CLOCK task, which keeps the synchronous alarms
+// of the processes. In Minix, this is implemented in kernel/clock.c
+
+use std::time::Duration;
+
+use crate::sys::tmr_is_first;
+use crate::utils::endpoint::CLOCK;
+use crate::utils::{ticks_to_duration, Endpoint, MinixProcessTable};
+
+/// sets the alarm of the process to expire at `exp_time` (in clock ticks)
+pub fn set_kernel_timer(endpoint: Endpoint, exp_time: u32, process_table: &mut MinixProcessTable) {
+    process_table[endpoint]
+        .privileges
+        .s_alarm_timer
+        .set(exp_time, endpoint);
+}
+
+pub fn reset_kernel_timer(endpoint: Endpoint, process_table: &mut MinixProcessTable) {
+    process_table[endpoint].privileges.s_alarm_timer.reset();
+}
+
+/// notifies the processes whose alarms expired, resetting their timers
+pub fn expire_timers(process_table: &mut MinixProcessTable) -> Result<(), nix::Error> {
+    let uptime = process_table.clock.uptime() as u32;
+
+    let endpoints: Vec<Endpoint> = process_table.endpoints().collect();
+    for endpoint in endpoints {
+        let timer = &mut process_table[endpoint].privileges.s_alarm_timer;
+        if !timer.is_set() || tmr_is_first(uptime, timer.exp_time()) {
+            continue;
+        }
+
+        let arg = timer.arg();
+        timer.reset();
+        cause_alarm(arg, process_table)?;
+    }
+
+    Ok(())
+}
+
+/// returns the time left until the first alarm expires,
+/// or `None` if no alarm is set
+pub fn next_timeout(process_table: &MinixProcessTable) -> Option<Duration> {
+    let uptime = process_table.clock.uptime() as u32;
+
+    process_table
+        .endpoints()
+        .map(|endpoint| &process_table[endpoint].privileges.s_alarm_timer)
+        .filter(|timer| timer.is_set())
+        .map(|timer| {
+            if tmr_is_first(uptime, timer.exp_time()) {
+                timer.exp_time().wrapping_sub(uptime)
+            } else {
+                0
+            }
+        })
+        .min()
+        .map(|ticks| ticks_to_duration(ticks as u64))
+}
+
+/// the alarm is delivered to the process as a notification from CLOCK
+fn cause_alarm(
+    endpoint: Endpoint,
+    process_table: &mut MinixProcessTable,
+) -> Result<(), nix::Error> {
+    crate::ipc::do_notify(CLOCK, endpoint, process_table)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::sys::{static_priv_id, Priv};
+    use crate::test_utils::{run_processes, test_bin};
+    use crate::utils::{priv_flags, MinixProcess};
+
+    /// runs the process, which sets an alarm and waits for the
+    /// notification from CLOCK, returning the time it took
+    fn run_alarm(name: &str) -> Duration {
+        let mut process_table = MinixProcessTable::new();
+
+        let mut process = MinixProcess::spawn(&test_bin(name)).unwrap();
+        process.s_flags = priv_flags::SYS_PROC;
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+        let start = Instant::now();
+
+        // the process exits with the source of the notification
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(42, CLOCK as u8 as i32)]);
+
+        start.elapsed()
+    }
+
+    #[test]
+    fn alarm_test() {
+        // the process sets an alarm 10ms from now
+        assert!(run_alarm("alarm") >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn abs_alarm_test() {
+        // the process sets an alarm at the absolute time 0,
+        // which is in the past, so it expires right away
+        assert!(run_alarm("alarm_abs") < Duration::from_secs(1));
+    }
+}
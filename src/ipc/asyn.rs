@@ -1,16 +1,16 @@
 use std::mem::size_of;
 
 use crate::{
-    ipc::{can_receive, will_receive},
+    ipc::{can_receive, deliver_message, ipcconst::SENDA, may_send_to, will_receive},
     utils::{
-        endpoint::{Endpoint, ANY, ASYNCM},
-        minix_errno::{EAGAIN, ECALLDENIED, EDEADSRCDST, EINVAL, ESRCH, OK},
-        Message, MinixProcess, MinixProcessTable,
+        endpoint::{endpoint_p, iskerneln, Endpoint, ANY, ASYNCM},
+        minix_errno::{EAGAIN, ECALLDENIED, EDEADSRCDST, EDOM, EINVAL, EPERM, ESRCH, OK},
+        priv_flags::SYS_PROC,
+        Message, MinixProcess, MinixProcessTable, ProcessState, TABLE_SIZE,
     },
 };
 
 /// bits for the `flags` field
-#[allow(dead_code)]
 mod asyn_flags {
     pub const AMF_EMPTY: u32 = 0; // slot is not in use
     pub const AMF_VALID: u32 = 1; // slot contains a message
@@ -19,6 +19,9 @@ mod asyn_flags {
     pub const AMF_NOTIFY: u32 = 4; // send a notification when AMF_DONE is set
     pub const AMF_NOREPLY: u32 = 8; // not a reply message for a SENDREC
     pub const AMF_NOTIFY_ERR: u32 = 16; // send a notification on error
+
+    // all the valid bits
+    pub const AMF_ALL: u32 = AMF_VALID | AMF_DONE | AMF_NOTIFY | AMF_NOREPLY | AMF_NOTIFY_ERR;
 }
 
 use asyn_flags::*;
@@ -31,49 +34,49 @@ struct AsynMsg {
     msg: Message,
 }
 
-pub fn has_pending_asend(
-    dst: Endpoint,
-    src: Endpoint,
-    process_table: &mut MinixProcessTable,
-) -> Result<bool, nix::Error> {
-    // TODO: check that caller has a priv structure
-
-    if process_table[dst].async_pending.is_empty() {
-        return Ok(false);
-    }
+/// checks if `src` has a pending asynchronous message for `dst`.
+/// If `src` is ANY, checks for messages from any source
+pub fn has_pending_asend(dst: Endpoint, src: Endpoint, process_table: &MinixProcessTable) -> bool {
+    let pending = &process_table[dst].privileges.s_asyn_pending;
 
     if src == ANY {
-        Ok(true)
+        pending.iter().next().is_some()
     } else {
-        Ok(process_table[dst].async_pending.contains(&src))
+        match process_table.priv_id(src) {
+            Some(id) => pending.get(id),
+            None => false,
+        }
     }
 }
 
+/// tries to receive an asynchronous message from `src` into `dst`,
+/// where `receive_e` is the endpoint `dst` is receiving from
 pub fn try_one(
+    receive_e: Endpoint,
     src: Endpoint,
     dst: Endpoint,
     process_table: &mut MinixProcessTable,
 ) -> Result<i32, nix::Error> {
-    // TODO: if `src` is not a system process,
-    // return EPERM
+    // only system processes may send asynchronous messages
+    if process_table[src].s_flags & SYS_PROC == 0 {
+        return Ok(EPERM);
+    }
+
     let size = process_table[src].privileges.s_asynsize;
     let table_addr = process_table[src].privileges.s_asyntab;
 
-    // clear the pending message
-    for (idx, &endpoint) in process_table[dst].async_pending.iter().enumerate() {
-        if endpoint == src {
-            process_table[dst].async_pending.remove(idx);
-            break;
-        }
-    }
+    // clear the pending message flag, we're done unless we're not
+    let id = process_table.priv_id(src).unwrap();
+    process_table[dst].privileges.s_asyn_pending.unset(id);
 
     if size == 0 {
         return Ok(EAGAIN);
     }
-    // here, Minix checks if the endpoint in `priv`
-    // is the same as the `src`. We don't set the
-    // endpoint in `priv` (yet), so we skip this check
-    if !may_asynsend_to(src, dst, process_table)? {
+    // the table may belong to a previous incarnation of the process
+    if process_table[src].privileges.s_asynendpoint != src {
+        return Ok(EAGAIN);
+    }
+    if !may_asynsend_to(src, dst, process_table) {
         return Ok(ECALLDENIED);
     }
 
@@ -81,6 +84,8 @@ pub fn try_one(
     let mut done = true;
     let mut r = EAGAIN;
 
+    // some checks done in `do_senda` are repeated here,
+    // since the sender could have modified its table in the meantime
     for i in 0..size {
         let addr = table_addr + i * size_of::<AsynMsg>() as u32;
         let mut tabent = read_asynmsg(addr as u64, &process_table[src])?;
@@ -91,7 +96,7 @@ pub fn try_one(
         }
 
         // `flags` field must contain only valid bits
-        if tabent.flags & !(AMF_VALID | AMF_DONE | AMF_NOTIFY | AMF_NOREPLY | AMF_NOTIFY_ERR) != 0
+        if tabent.flags & !AMF_ALL != 0
             // must contain a message
             || tabent.flags & AMF_VALID == 0
         {
@@ -101,6 +106,7 @@ pub fn try_one(
             continue;
         }
 
+        // we're done when all messages have been processed
         done = false;
 
         if r != EINVAL {
@@ -110,7 +116,7 @@ pub fn try_one(
                 continue;
             }
 
-            if !can_receive(dst, src) {
+            if !can_receive(receive_e, src) {
                 continue;
             }
 
@@ -123,13 +129,12 @@ pub fn try_one(
 
             // destination is ready to receive the message; deliver it
             r = OK;
-            let dst = &process_table[dst];
             let mut message = tabent.msg;
             message.source = src;
-            let addr = dst.get_regs()?.rbx;
-            dst.write_message(addr, message)?;
+            deliver_message(&mut process_table[dst], message, SENDA)?;
         }
 
+        // store the results for the sender
         tabent.result = r;
         tabent.flags |= AMF_DONE;
         if (tabent.flags & AMF_NOTIFY) != 0 || (r != OK && tabent.flags & AMF_NOTIFY_ERR != 0) {
@@ -138,6 +143,8 @@ pub fn try_one(
 
         write_asynmsg(tabent, addr as u64, &process_table[src])?;
 
+        // like in Minix, one entry is processed per call: either the
+        // delivered message, or a bad entry, which is done with EINVAL
         break;
     }
 
@@ -150,20 +157,29 @@ pub fn try_one(
         privileges.s_asyntab = (-1i32) as u32;
         privileges.s_asynsize = 0;
     } else {
-        process_table[dst].notify_pending.push(src);
+        process_table[dst].privileges.s_asyn_pending.set(id);
     }
 
     Ok(r)
 }
 
+/// tries to receive an asynchronous message from any source
 pub fn try_async(
     caller: Endpoint,
     process_table: &mut MinixProcessTable,
 ) -> Result<i32, nix::Error> {
-    let async_pending = process_table[caller].async_pending.clone();
-
-    for src in async_pending {
-        let result = try_one(src, caller, process_table)?;
+    let pending: Vec<usize> = process_table[caller]
+        .privileges
+        .s_asyn_pending
+        .iter()
+        .collect();
+
+    for id in pending {
+        let src = match process_table.id_to_endpoint(id) {
+            Some(src) => src,
+            None => continue,
+        };
+        let result = try_one(ANY, src, caller, process_table)?;
         if result == OK {
             return Ok(result);
         }
@@ -178,7 +194,10 @@ pub fn do_senda(
     size: u64,
     process_table: &mut MinixProcessTable,
 ) -> Result<i32, nix::Error> {
-    // TODO: check if caller is a system process
+    // only system processes may send asynchronous messages
+    if process_table[caller].s_flags & SYS_PROC == 0 {
+        return Ok(EPERM);
+    }
 
     let privileges = &mut process_table[caller].privileges;
     privileges.s_asyntab = (-1i32) as u32;
@@ -189,7 +208,13 @@ pub fn do_senda(
         return Ok(OK);
     }
 
-    let mut r;
+    // limit the size to something reasonable
+    if size > 16 * TABLE_SIZE as u64 {
+        return Ok(EDOM);
+    }
+
+    // the result of the last entry processed is returned
+    let mut r = OK;
     let mut do_notify = false;
     let mut done = true;
     let mut dst;
@@ -205,20 +230,14 @@ pub fn do_senda(
             continue;
         }
 
-        if tabent.flags & !(AMF_VALID | AMF_DONE | AMF_NOTIFY | AMF_NOREPLY | AMF_NOTIFY_ERR) != 0 {
-            // TODO: Minix sets r to EINVAL here, but doesn't
-            // actually write it to the async table in process?
-
+        if tabent.flags & !AMF_ALL != 0 {
             // `flags` field must contain only valid bits
-            println!("KERNEL senda error {} to {:?}: invalid flags", caller, dst);
-            continue;
+            println!("KERNEL senda error {} to {}: invalid flags", caller, dst);
+            return Ok(EINVAL);
         } else if tabent.flags & AMF_VALID == 0 {
             // must contain a message
-            println!(
-                "KERNEL senda error {} to {:?}: AMF_VALID unset",
-                caller, dst
-            );
-            continue;
+            println!("KERNEL senda error {} to {}: AMF_VALID unset", caller, dst);
+            return Ok(EINVAL);
         } else if tabent.flags & AMF_DONE != 0 {
             // already done processing
             continue;
@@ -226,38 +245,36 @@ pub fn do_senda(
 
         r = OK;
 
-        // TODO: check if dst is kernel, return ECALLDENIED if so
-        if process_table.get(dst).is_none() {
+        if !process_table.isokendpt(dst) {
             r = EDEADSRCDST; // bad destination
-        } else if !may_asynsend_to(caller, dst, process_table)? {
-            r = ECALLDENIED; // send denied by ipcmask (not implemented yet)
+        } else if iskerneln(endpoint_p(dst)) {
+            r = ECALLDENIED; // asynchronous sends to the kernel are not allowed
+        } else if !may_asynsend_to(caller, dst, process_table) {
+            r = ECALLDENIED; // send denied by ipcmask
         }
 
         // Check if `dst` is blocked waiting for this message.
-        // If AMF_NOREPLY is set, do not send to a SENDREC
+        // If AMF_NOREPLY is set, do not satisfy the receiving part of a SENDREC
         if r == OK
             && will_receive(caller, dst, process_table)
-            && (tabent.flags & AMF_NOREPLY != 0 || !process_table[dst].reply_pending)
+            && (tabent.flags & AMF_NOREPLY == 0 || !process_table[dst].reply_pending)
         {
             let mut message = tabent.msg;
             message.source = caller;
-            let addr = process_table[dst].get_regs()?.rbx;
-            process_table[dst].write_message(addr, message)?;
+            deliver_message(&mut process_table[dst], message, SENDA)?;
 
-            // unset the receiving flag, resume process if `Running`
+            // unset the receiving flag and resume the process
             process_table[dst].state = match process_table[dst].state {
-                crate::utils::ProcessState::Receiving(_) => {
+                ProcessState::Receiving(_) => {
                     process_table[dst].cont()?;
-                    crate::utils::ProcessState::Running
+                    ProcessState::Running
                 }
-                crate::utils::ProcessState::SendReceiving(src_dst) => {
-                    crate::utils::ProcessState::Sending(src_dst)
-                }
-                _ => unreachable!("Process must be either SENDING or SENDRECing"),
+                _ => unreachable!("Process must be RECEIVING"),
             }
         } else if r == OK {
-            // add a pending async message to the receiver
-            process_table[dst].async_pending.push(caller);
+            // inform the receiver that something is pending
+            let id = process_table.priv_id(caller).unwrap();
+            process_table[dst].privileges.s_asyn_pending.set(id);
             done = false;
             continue;
         }
@@ -282,17 +299,13 @@ pub fn do_senda(
         process.privileges.s_asynsize = size as u32;
     }
 
-    Ok(OK)
+    Ok(r)
 }
 
-// TODO:
-#[allow(clippy::unnecessary_wraps)]
-fn may_asynsend_to(
-    _src: Endpoint,
-    _dst: Endpoint,
-    _process_table: &mut MinixProcessTable,
-) -> Result<bool, nix::Error> {
-    Ok(true)
+// Minix additionally checks the destination's ipc filters here,
+// which we don't support
+fn may_asynsend_to(src: Endpoint, dst: Endpoint, process_table: &MinixProcessTable) -> bool {
+    may_send_to(src, dst, process_table)
 }
 
 fn read_asynmsg(addr: u64, process: &MinixProcess) -> Result<AsynMsg, nix::Error> {
@@ -304,7 +317,7 @@ fn read_asynmsg(addr: u64, process: &MinixProcess) -> Result<AsynMsg, nix::Error
         *dst = src;
     }
 
-    Ok(unsafe { std::mem::transmute(result) })
+    Ok(unsafe { std::mem::transmute::<[u8; size_of::<AsynMsg>()], AsynMsg>(result) })
 }
 
 fn write_asynmsg(asynmsg: AsynMsg, addr: u64, process: &MinixProcess) -> Result<(), nix::Error> {
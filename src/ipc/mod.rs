@@ -1,9 +1,11 @@
-#[allow(dead_code)]
 mod asyn;
 
-use crate::utils::{endpoint, Endpoint, Message, NOTIFY_MESSAGE};
+use crate::sys::SigSet;
+use crate::utils::{endpoint, Endpoint, Message, MessagePayload, Payload, NOTIFY_MESSAGE};
 use crate::utils::{
-    minix_errno::{self, EDEADSRCDST, EINVAL, ENOTREADY, OK},
+    minix_errno::{
+        self, EBADCALL, ECALLDENIED, EDEADSRCDST, EINVAL, ELOCKED, ENOTREADY, ETRAPDENIED, OK,
+    },
     MinixProcess,
 };
 use crate::utils::{MinixProcessTable, ProcessState};
@@ -22,6 +24,16 @@ mod ipcconst {
     pub const SENDA: u64 = 16; // asynchronous send
 }
 
+#[allow(dead_code)]
+mod ipc_status {
+    // ipc status word layout, defined in minix/ipc.h
+    pub const IPC_FLG_MSG_FROM_KERNEL: u64 = 1; // message originated in the kernel
+    pub const IPC_STATUS_CLEAR: u64 = 0;
+    pub const IPC_STATUS_CALL_SHIFT: u64 = 3;
+    pub const IPC_STATUS_CALL_MASK: u64 = 0x3F;
+    pub const IPC_STATUS_FLAGS_SHIFT: u64 = 0;
+}
+
 /// handles the ipc calls from processes
 pub fn do_ipc(
     caller_endpoint: Endpoint,
@@ -33,18 +45,15 @@ pub fn do_ipc(
     let mut regs = process.get_regs()?;
     // advance the instruction pointer to the next instruction
     regs.rip += 2;
+    // remember the message buffer, and clear the ipc
+    // status, which is set again on message delivery
+    let msg_ptr = regs.rbx;
+    process.delivermsg_vir = msg_ptr;
+    regs.rbx = ipc_status::IPC_STATUS_CLEAR;
     process.set_regs(regs).unwrap();
 
     let call_nr = regs.rcx;
 
-    // TODO: if call is SEND, SENDNB, SENDREC or NOTIFY, verify
-    // that the caller is allowed to send to the given destination
-    // else return ECALLDENIED in process.
-
-    // TODO: check if the process has privileges for the requested call.
-    // Calls to the kernel may only be SENDREC because tasks always reply.
-    // If illegal, return ETRAPDENIED in process.
-
     // the ecx register contains the type of ipc call
     let result = match call_nr {
         ipcconst::SEND..=ipcconst::SENDNB => do_sync_ipc(
@@ -53,7 +62,7 @@ pub fn do_ipc(
             regs.rax as Endpoint,
             process_table,
         )?,
-        ipcconst::SENDA => asyn::do_senda(caller_endpoint, regs.rbx, regs.rax, process_table)?,
+        ipcconst::SENDA => asyn::do_senda(caller_endpoint, msg_ptr, regs.rax, process_table)?,
         ipcconst::MINIX_KERNINFO => {
             // check if process has the `minix_kerninfo` struct already mapped
             // and if not, map it to the process's memory
@@ -81,14 +90,18 @@ pub fn do_ipc(
         }
         _ => {
             // invalid call number - return EBADCALL in process
-            todo!("invalid ipc call number")
+            println!(
+                "ipc: invalid call number {} from {}",
+                call_nr as i64, caller_endpoint
+            );
+            EBADCALL
         }
     };
 
-    // if the caller doesn't have any ipc state set,
-    // set the ipc call return value and resume it
+    // set the ipc call return value. If the caller
+    // doesn't have any ipc state set, resume it
+    set_return_value(&process_table[caller_endpoint], result)?;
     if let ProcessState::Running = process_table[caller_endpoint].state {
-        set_return_value(&process_table[caller_endpoint], result)?;
         process_table[caller_endpoint].cont()?;
     };
 
@@ -108,13 +121,31 @@ fn do_sync_ipc(
     }
 
     // check the source / destination is valid
-    if dest_src != endpoint::ANY && process_table.get(dest_src).is_none() {
+    if dest_src != endpoint::ANY && !process_table.isokendpt(dest_src) {
         // return EDEADSRCDST in process
-        todo!(
-            "Handle invalid endpoints. Endpoint: {}, call: {}",
-            dest_src,
-            call_nr
-        );
+        return Ok(EDEADSRCDST);
+    }
+
+    // if the call is SEND, SENDNB, SENDREC or NOTIFY, verify
+    // that the caller is allowed to send to the given destination
+    if call_nr != ipcconst::RECEIVE && !may_send_to(caller, dest_src, process_table) {
+        return Ok(ECALLDENIED);
+    }
+
+    // only then, like in Minix, check if the process
+    // has privileges for the requested call
+    if !process_table[caller].privileges.may_trap(call_nr) {
+        return Ok(ETRAPDENIED);
+    }
+
+    // calls to the kernel may only be SENDREC, because tasks always reply
+    // and may not block if the caller doesn't do receive()
+    if call_nr != ipcconst::SENDREC
+        && call_nr != ipcconst::RECEIVE
+        && dest_src != endpoint::ANY
+        && endpoint::iskerneln(endpoint::endpoint_p(dest_src))
+    {
+        return Ok(ETRAPDENIED);
     }
 
     let result = match call_nr {
@@ -138,7 +169,7 @@ fn do_send(
     process_table: &mut MinixProcessTable,
     non_blocking: bool,
 ) -> Result<i32, nix::Error> {
-    let addr = process_table[caller].get_regs()?.rbx;
+    let addr = process_table[caller].delivermsg_vir;
     let mut message = process_table[caller].read_message(addr)?; // TODO: return EFAULT in child if read is not successful
 
     // check if `dst` is blocked waiting for this message
@@ -149,17 +180,17 @@ fn do_send(
         // set the source of the message
         message.source = caller;
 
-        // TODO: minix sets call status in receiver here
+        let call_nr = if process_table[caller].reply_pending {
+            ipcconst::SENDREC
+        } else if non_blocking {
+            ipcconst::SENDNB
+        } else {
+            ipcconst::SEND
+        };
 
-        let receiver = &mut process_table[dst];
         // write the message to the receiver's memory
-        let addr = receiver.get_regs()?.rbx;
-
-        receiver.write_message(addr, message)?;
-
-        println!("{:016x?}", receiver.read_message(addr).unwrap());
-
-        // TODO: set the IPC status in receiver
+        let receiver = &mut process_table[dst];
+        deliver_message(receiver, message, call_nr)?;
 
         // unset the `RECEIVING` status in `dst`
         receiver.state = match receiver.state {
@@ -176,8 +207,10 @@ fn do_send(
             return Ok(ENOTREADY);
         }
 
-        // TODO: Minix checks for a possible deadlock,
-        // and returns ELOCKED in sender if detected
+        // check for a possible deadlock before actually blocking
+        if deadlock(ipcconst::SEND, caller, dst, process_table) {
+            return Ok(ELOCKED);
+        }
 
         // set the sender's state as SENDING to the receiver
         // this blocks the sender, waiting for the receiver
@@ -205,42 +238,55 @@ fn do_receive(
         return Ok(OK);
     };
 
-    // check if there are pending notifications, except for SENDREC
+    // check if there are pending notifications, except for SENDREC.
+    // They're delivered in the order of the senders' privilege ids
     if !process_table[caller].reply_pending {
-        for (index, &src) in process_table[caller].notify_pending.iter().enumerate() {
-            if can_receive(caller, src) {
+        let pending: Vec<usize> = process_table[caller]
+            .privileges
+            .s_notify_pending
+            .iter()
+            .collect();
+        for id in pending {
+            let notify_src = match process_table.id_to_endpoint(id) {
+                Some(notify_src) => notify_src,
+                None => continue,
+            };
+            if can_receive(src, notify_src) {
+                let uptime = process_table.clock.uptime();
                 let receiver = &mut process_table[caller];
-                receiver.notify_pending.remove(index);
-                let msg = build_notify_message(src);
-                let addr = receiver.get_regs()?.rbx;
-                receiver.write_message(addr, msg)?;
-                // TODO: set ipc status
+                receiver.privileges.s_notify_pending.unset(id);
+                let msg = build_notify_message(notify_src, receiver, uptime);
+                deliver_message(receiver, msg, ipcconst::NOTIFY)?;
                 return Ok(OK);
             }
         }
     }
 
     // check for pending asynchronous messages
-    if has_pending_asend(caller, src, process_table)? {
+    if has_pending_asend(caller, src, process_table) {
         let r = if src != endpoint::ANY {
-            asyn::try_one(src, caller, process_table)?
+            asyn::try_one(src, src, caller, process_table)?
         } else {
             asyn::try_async(caller, process_table)?
         };
 
         if r == OK {
-            // TODO: set ipc status
             return Ok(OK);
         }
     }
 
     // look on the queue for an appropriate message
-    if let Some((sender, mut message)) = process_table[caller]
+    if let Some((sender_endpoint, mut message)) = process_table[caller]
         .queue
         .get(|sender| can_receive(src, sender))
     {
         // unset the `SENDING` state in sender
-        let sender = &mut process_table[sender];
+        let sender = &mut process_table[sender_endpoint];
+        let call_nr = if sender.reply_pending {
+            ipcconst::SENDREC
+        } else {
+            ipcconst::SEND
+        };
         sender.state = match sender.state {
             ProcessState::SendReceiving(dst) => ProcessState::Receiving(dst),
             ProcessState::Sending(_) => {
@@ -253,17 +299,10 @@ fn do_receive(
         };
 
         // set the source of the message
-        message.source = caller;
+        message.source = sender_endpoint;
 
         // write the message to receiver
-        let receiver = &process_table[caller];
-        let addr = receiver.get_regs()?.rbx;
-        receiver.write_message(addr, message)?;
-
-        println!("{:016x?}", receiver.read_message(addr).unwrap());
-
-        // TODO: set the IPC status here
-        // status is stored in the ebx register
+        deliver_message(&mut process_table[caller], message, call_nr)?;
         return Ok(OK);
     }
 
@@ -272,7 +311,10 @@ fn do_receive(
     // However, `mini_receive` is only called with empty
     // flags, so this never actually happens.
 
-    // TODO: before block, check for deadlocks
+    // check for a possible deadlock before actually blocking
+    if deadlock(ipcconst::RECEIVE, caller, src, process_table) {
+        return Ok(ELOCKED);
+    }
 
     // set the caller as `RECEIVING` from `src`
     process_table[caller].state = ProcessState::Receiving(src);
@@ -284,41 +326,43 @@ fn do_sendrec(
     dst: Endpoint,
     process_table: &mut MinixProcessTable,
 ) -> Result<i32, nix::Error> {
+    // the flag is set, so that notifications cannot interrupt SENDREC
     process_table[caller].reply_pending = true;
     let send_result = do_send(caller, dst, process_table, false)?;
     if send_result != OK {
+        // no reply is coming
+        process_table[caller].reply_pending = false;
         return Ok(send_result);
     }
     do_receive(caller, dst, process_table)
 }
 
-fn do_notify(
+pub fn do_notify(
     caller: Endpoint,
     dst: Endpoint,
     process_table: &mut MinixProcessTable,
 ) -> Result<i32, nix::Error> {
-    if process_table.get(dst).is_none() {
+    if !process_table.isokendpt(dst) {
         // TODO: return EDEADSRCDST from ipc call
         return Ok(EDEADSRCDST);
     }
 
-    if !will_receive(caller, dst, process_table) {
-        process_table[dst].notify_pending.push(caller);
+    // a process blocked in SENDREC may only be woken up by the reply,
+    // so the notification is left pending until then
+    if !will_receive(caller, dst, process_table) || process_table[dst].reply_pending {
+        // repeated notifications from one source coalesce into one pending bit
+        let id = process_table.priv_id(caller).unwrap();
+        process_table[dst].privileges.s_notify_pending.set(id);
         return Ok(OK);
     }
 
-    // TODO: check the 'sendrecing' flag (`MF_REPLY_PEND`)
-    // once that's implemented
-
-    let msg = build_notify_message(caller);
+    let uptime = process_table.clock.uptime();
     let receiver = &mut process_table[dst];
-    let addr = receiver.get_regs()?.rbx;
-    receiver.write_message(addr, msg)?;
+    let msg = build_notify_message(caller, receiver, uptime);
+    deliver_message(receiver, msg, ipcconst::NOTIFY)?;
 
     // set the receiver status to running and run it
 
-    // TODO: set the IPC status in receiver
-
     // unset the `RECEIVING` status in `dst`
     receiver.state = match receiver.state {
         ProcessState::Receiving(_) => {
@@ -331,9 +375,86 @@ fn do_notify(
     Ok(OK)
 }
 
+// checks for a deadlock, which can happen if `caller` and `src_dst`
+// have a cyclic dependency of blocking send and receive calls.
+// The only cyclic dependency that is not fatal is if the caller
+// and target directly SEND(REC) and RECEIVE to each other.
+fn deadlock(
+    call_nr: u64,
+    caller: Endpoint,
+    mut src_dst: Endpoint,
+    process_table: &MinixProcessTable,
+) -> bool {
+    let mut group_size = 1; // start with only caller
+    while src_dst != endpoint::ANY {
+        // follow the chain of processes
+        let process = match process_table.get(src_dst) {
+            Some(process) => process,
+            None => return false,
+        };
+        group_size += 1;
+
+        // if the last process in the chain isn't blocked,
+        // the cycle cannot be closed
+        src_dst = process.state.blocked_on();
+        if src_dst == endpoint::NONE {
+            return false;
+        }
+
+        if src_dst == caller {
+            // for group sizes of two, a combination of
+            // SEND(REC) and RECEIVE is not fatal
+            if group_size == 2 && process.state.is_sending() != (call_nr == ipcconst::SEND) {
+                return false;
+            }
+            return true;
+        }
+    }
+
+    false
+}
+
+// checks the destination against the caller's ipc mask.
+// Assumes both endpoints are valid
+fn may_send_to(caller: Endpoint, dst: Endpoint, process_table: &MinixProcessTable) -> bool {
+    let id = process_table[dst].privileges.s_id as usize;
+    process_table[caller].privileges.s_ipc_to.get(id)
+}
+
+// writes the message to the buffer of the receiving process,
+// and sets the ipc status, which is returned in the ebx register.
+// Since the receive is done, the receiver no longer waits for a reply
+pub fn deliver_message(
+    receiver: &mut MinixProcess,
+    message: Message,
+    call_nr: u64,
+) -> Result<(), nix::Error> {
+    use ipc_status::*;
+
+    // the registers were restored to a context from before the ipc call
+    // (e.g. by SYS_SIGRETURN), so the message buffer isn't known anymore
+    if receiver.is_context_set() {
+        receiver.reply_pending = false;
+        return Ok(());
+    }
+
+    receiver.write_message(receiver.delivermsg_vir, message)?;
+    let mut regs = receiver.get_regs()?;
+    regs.rbx = (call_nr & IPC_STATUS_CALL_MASK) << IPC_STATUS_CALL_SHIFT;
+    if endpoint::iskerneln(endpoint::endpoint_p(message.source)) {
+        regs.rbx |= IPC_FLG_MSG_FROM_KERNEL << IPC_STATUS_FLAGS_SHIFT;
+    }
+    receiver.set_regs(regs)?;
+    receiver.reply_pending = false;
+    Ok(())
+}
+
 // sets the rax register to be the return value
 // of the ipc call
-fn set_return_value(process: &MinixProcess, value: i32) -> Result<(), nix::Error> {
+pub fn set_return_value(process: &MinixProcess, value: i32) -> Result<(), nix::Error> {
+    if process.is_context_set() {
+        return Ok(());
+    }
     let mut regs = process.get_regs()?;
     regs.rax = value as u64;
     process.set_regs(regs)
@@ -356,11 +477,274 @@ fn can_receive(receive_e: Endpoint, sender: Endpoint) -> bool {
     receive_e == endpoint::ANY || receive_e == sender
 }
 
-// TODO: fill the notify with payload when appropriate
-fn build_notify_message(src: Endpoint) -> Message {
+// builds the notification message from `src` to `receiver`.
+// Notifications from HARDWARE and SYSTEM carry (and clear)
+// the receiver's pending interrupts and signals
+fn build_notify_message(src: Endpoint, receiver: &mut MinixProcess, uptime: u64) -> Message {
+    let mut payload = MessageNotify {
+        timestamp: uptime,
+        interrupts: 0,
+        sigset: Default::default(),
+        padding: [0; 24],
+    };
+
+    match src {
+        endpoint::HARDWARE => {
+            payload.interrupts = receiver.privileges.s_int_pending as u64;
+            receiver.privileges.s_int_pending = 0;
+        }
+        endpoint::SYSTEM => {
+            payload.sigset = std::mem::take(&mut receiver.privileges.s_sig_pending);
+        }
+        _ => {}
+    }
+
     Message {
         m_type: NOTIFY_MESSAGE,
         source: src,
-        payload: [0; 14],
+        payload: payload.into_payload(),
+    }
+}
+
+/// the payload of a notification message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageNotify {
+    timestamp: u64,  // uptime in clock ticks, valid for every notification
+    interrupts: u64, // raised interrupts, valid if from HARDWARE
+    sigset: SigSet,  // raised signals, valid if from SYSTEM
+
+    padding: [u8; 24],
+}
+assert_eq_size!(MessageNotify, MessagePayload);
+impl Payload for MessageNotify {}
+
+#[cfg(test)]
+mod tests {
+    use nix::sys::{signal::Signal::SIGSEGV, wait::WaitStatus};
+
+    use super::do_ipc;
+    use crate::sys::{static_priv_id, Priv};
+    use crate::test_utils::{run_processes, run_processes_with, test_bin};
+    use crate::utils::{
+        endpoint,
+        minix_errno::{EBADCALL, EDEADSRCDST},
+        priv_flags, MinixProcess, MinixProcessTable,
+    };
+
+    #[test]
+    fn bad_call_test() {
+        let mut process_table = MinixProcessTable::new();
+        let _ = process_table.insert(MinixProcess::spawn(&test_bin("bad_ipc_call")).unwrap(), 42);
+
+        // the process exits with the result of the ipc call
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(42, EBADCALL as u8 as i32)]);
+    }
+
+    #[test]
+    fn senda_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 20 sends a message to 21 with SENDA, and asks for an ASYNCM
+        // notification once it's delivered. Only system processes may use SENDA
+        let mut sender = MinixProcess::spawn(&test_bin("senda_20")).unwrap();
+        sender.s_flags = priv_flags::SYS_PROC;
+        sender.privileges = Priv::system(static_priv_id(20));
+        let mut receiver = MinixProcess::spawn(&test_bin("senda_21")).unwrap();
+        receiver.privileges = Priv::system(static_priv_id(21));
+        let _ = process_table.insert(receiver, 21);
+        let _ = process_table.insert(sender, 20);
+
+        // 21 exits with the type of the message, and 20 with
+        // the flags of the table entry, after receiving the notification
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(20, 1 | 2 | 4), (21, 7)]);
+    }
+
+    #[test]
+    fn senda_error_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 20 sends a table with a bad entry, which is rejected untouched, and
+        // then one whose last entry is for a dead endpoint, which is returned
+        let mut sender = MinixProcess::spawn(&test_bin("senda_error_20")).unwrap();
+        sender.s_flags = priv_flags::SYS_PROC;
+        sender.privileges = Priv::system(static_priv_id(20));
+        let mut receiver = MinixProcess::spawn(&test_bin("senda_21")).unwrap();
+        receiver.privileges = Priv::system(static_priv_id(21));
+        let sender_pid = sender.pid();
+        let receiver_pid = receiver.pid();
+        let _ = process_table.insert(receiver, 21);
+        let _ = process_table.insert(sender, 20);
+
+        // the table is read while 21 receives, which needs 20 to be stopped,
+        // so 21's call waits until 20 blocks in RECEIVE after both SENDA calls
+        let mut sender_calls = 0;
+        let mut receiver_waiting = false;
+        let statuses =
+            run_processes_with(&mut process_table, |status, process_table| match *status {
+                WaitStatus::Stopped(pid, SIGSEGV) if pid == receiver_pid && sender_calls < 3 => {
+                    receiver_waiting = true;
+                    false
+                }
+                WaitStatus::Stopped(pid, SIGSEGV) if pid == sender_pid => {
+                    sender_calls += 1;
+                    if sender_calls < 3 || !receiver_waiting {
+                        return true;
+                    }
+                    do_ipc(20, process_table).unwrap();
+                    do_ipc(21, process_table).unwrap();
+                    false
+                }
+                _ => true,
+            });
+
+        // 21 exits with the type of the message, and 20 with the flags
+        // of the delivered entry, after checking the results
+        assert_eq!(statuses, vec![(20, 1 | 2 | 4), (21, 7)]);
+    }
+
+    #[test]
+    fn senda_noreply_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 41 sends a message with AMF_NOREPLY to 42, which waits for its reply
+        // in SENDREC. 42 gets the reply first, and the message on its next receive
+        for endpoint in [41, 42] {
+            let path = test_bin(&format!("noreply_{}", endpoint));
+            let mut process = MinixProcess::spawn(&path).unwrap();
+            process.s_flags = priv_flags::SYS_PROC;
+            process.privileges = Priv::system(static_priv_id(endpoint));
+            let _ = process_table.insert(process, endpoint);
+        }
+
+        // both processes exit with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(41, 0), (42, 0)]);
+    }
+
+    #[test]
+    fn notify_order_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 43 and then 42 notify 44, which receives the notifications in the
+        // order of the senders' privilege ids. The user process 45 may not notify
+        for endpoint in 42..45 {
+            let path = test_bin(&format!("notify_order_{}", endpoint));
+            let mut process = MinixProcess::spawn(&path).unwrap();
+            process.privileges = Priv::system(static_priv_id(endpoint));
+            let _ = process_table.insert(process, endpoint);
+        }
+        let user = MinixProcess::spawn(&test_bin("notify_order_45")).unwrap();
+        let _ = process_table.insert(user, 45);
+
+        // all the processes exit with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(42, 0), (43, 0), (44, 0), (45, 0)]);
+    }
+
+    #[test]
+    fn notify_payload_test() {
+        use crate::sys::signal::{SIGKMESS, SIGKSIG};
+
+        let mut process_table = MinixProcessTable::new();
+        let mut process = MinixProcess::spawn(&test_bin("notify_payload")).unwrap();
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+
+        // raise system signals and interrupts before the process receives.
+        // The notifications carry them, and clear them on delivery
+        crate::sys::send_sig(42, SIGKMESS, &mut process_table).unwrap();
+        crate::sys::send_sig(42, SIGKSIG, &mut process_table).unwrap();
+        process_table[42].privileges.s_int_pending = 1 << 2;
+        super::do_notify(endpoint::HARDWARE, 42, &mut process_table).unwrap();
+        // the payload is built on delivery, so a later interrupt is carried too
+        process_table[42].privileges.s_int_pending |= 1;
+
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            if let WaitStatus::Exited(_, _) = status {
+                let privileges = &process_table[42].privileges;
+                assert_eq!(privileges.s_int_pending, 0);
+                assert!(privileges.s_sig_pending.is_empty());
+            }
+            true
+        });
+
+        // the process exits with 0 on success
+        assert_eq!(statuses, vec![(42, 0)]);
+    }
+
+    #[test]
+    fn call_denied_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 41 may not send to 42, so its SEND and NOTIFY to 42 fail with
+        // ECALLDENIED. 42 waits for its reply from 41 in SENDREC, until
+        // 41 exits and it gets EDEADSRCDST
+        let mut process = MinixProcess::spawn(&test_bin("ipc_denied_41")).unwrap();
+        process.privileges = Priv::system(static_priv_id(41));
+        process
+            .privileges
+            .s_ipc_to
+            .unset(static_priv_id(42) as usize);
+        let _ = process_table.insert(process, 41);
+        let mut process = MinixProcess::spawn(&test_bin("exit_42")).unwrap();
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+
+        // both processes exit with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(41, 0), (42, 0)]);
+    }
+
+    #[test]
+    fn dead_dest_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // a user process may not SEND, but the destination is checked
+        // first, so its SEND to the missing 42 fails with EDEADSRCDST
+        let process = MinixProcess::spawn(&test_bin("send_dead")).unwrap();
+        let _ = process_table.insert(process, 41);
+
+        // the process exits with the result of the SEND
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(41, EDEADSRCDST as u8 as i32)]);
+    }
+
+    #[test]
+    fn reply_pending_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 41 notifies 42 while 42 waits for its reply in SENDREC. The
+        // notification only wakes 42 up on its next receive, after the reply
+        for endpoint in [41, 42] {
+            let path = test_bin(&format!("reply_pend_{}", endpoint));
+            let mut process = MinixProcess::spawn(&path).unwrap();
+            process.privileges = Priv::system(static_priv_id(endpoint));
+            let _ = process_table.insert(process, endpoint);
+        }
+
+        // both processes exit with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(41, 0), (42, 0)]);
+    }
+
+    #[test]
+    fn ipcvecs_test() {
+        let mut process_table = MinixProcessTable::new();
+        process_table
+            .usermapped_mem
+            .write(0, &process_table.usermapped)
+            .unwrap();
+        let mut process = MinixProcess::spawn(&test_bin("ipcvecs")).unwrap();
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+
+        // the process maps the usermapped page with MINIX_KERNINFO,
+        // then sends a message through the `send` trampoline, and
+        // exits with the result of the ipc call
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(42, EDEADSRCDST as u8 as i32)]);
     }
 }
@@ -1,50 +1,89 @@
-use nix::sys::wait::wait;
-use nix::sys::{signal::Signal::SIGSEGV, wait::WaitStatus};
+use nix::sys::{
+    signal::Signal::{SIGSEGV, SIGSTOP},
+    wait::WaitStatus,
+};
 
-use utils::{priv_flags, MinixProcessTable, SharedImage, SharedMemory};
-use utils::{Instruction, MinixProcess};
+use utils::{priv_flags, MinixProcessTable};
+use utils::{ChildWaiter, Instruction, MinixProcess};
 
 const HZ: u32 = 16 * 1024 * 1024; // arbitrary 16 MHz
 
 #[macro_use]
 extern crate static_assertions;
 
+mod clock_task;
 mod ipc;
 mod sys;
+#[cfg(test)]
+mod test_utils;
 mod utils;
 
 fn main() {
     let mut process_table = MinixProcessTable::new();
 
     // prepare the usermapped memory
-    let usermapped_mem = SharedMemory::new("minix_usermapped", 4096).unwrap();
-    let usermapped = SharedImage::default();
-    usermapped_mem.write(0, &usermapped).unwrap();
+    process_table.usermapped.kclockinfo = process_table.clock.clockinfo();
+    process_table
+        .usermapped_mem
+        .write(0, &process_table.usermapped)
+        .unwrap();
+
+    // keep the clock information in the usermapped memory current
+    let kclockinfo_offset =
+        process_table.usermapped.minix_kerninfo.kclockinfo_ptr - utils::SHARED_BASE_ADDR;
+    utils::spawn_clockinfo_updater(
+        process_table.clock.clone(),
+        &process_table.usermapped_mem,
+        kclockinfo_offset as usize,
+    )
+    .unwrap();
 
     // setup the boot processes
     let mut rs = MinixProcess::spawn("server_bin/rs").unwrap();
-    rs.s_flags = priv_flags::ROOT_SYS_PROC;
+    rs.s_flags = priv_flags::SYS_PROC | priv_flags::PREEMPTIBLE | priv_flags::ROOT_SYS_PROC;
+    rs.privileges = sys::Priv::system(sys::static_priv_id(utils::endpoint::RS_PROC_NR));
+    rs.privileges.s_sig_mgr = utils::endpoint::SELF;
+    let mut is = MinixProcess::spawn("server_bin/is").unwrap();
+    is.s_flags = priv_flags::SYS_PROC | priv_flags::PREEMPTIBLE;
+    is.privileges = sys::Priv::system(sys::static_priv_id(12));
+    is.privileges.s_sig_mgr = utils::endpoint::RS_PROC_NR;
+    let mut ipc = MinixProcess::spawn("server_bin/ipc").unwrap();
+    ipc.s_flags = priv_flags::SYS_PROC | priv_flags::PREEMPTIBLE;
+    ipc.privileges = sys::Priv::system(sys::static_priv_id(13));
+    ipc.privileges.s_sig_mgr = utils::endpoint::RS_PROC_NR;
 
     let _ = process_table.insert(rs, utils::endpoint::RS_PROC_NR);
-    let _ = process_table.insert(MinixProcess::spawn("server_bin/is").unwrap(), 12); // arbitrary endpoint for testing
-    let _ = process_table.insert(MinixProcess::spawn("server_bin/ipc").unwrap(), 13); // arbitrary endpoint for testing
+    let _ = process_table.insert(is, 12); // arbitrary endpoint for testing
+    let _ = process_table.insert(ipc, 13); // arbitrary endpoint for testing
 
     main_loop(&mut process_table).unwrap();
 }
 
 fn main_loop(process_table: &mut MinixProcessTable) -> Result<(), nix::Error> {
+    let waiter = ChildWaiter::new()?;
+
     loop {
-        match wait().unwrap() {
+        // deliver the expired alarms, then wait for
+        // the processes until the next alarm is due
+        clock_task::expire_timers(process_table)?;
+        let status = match waiter.wait(clock_task::next_timeout(process_table))? {
+            Some(status) => status,
+            None => continue,
+        };
+
+        match status {
             WaitStatus::Stopped(pid, SIGSEGV) => {
                 // on SIGSEGV, check if segfault was caused by INT 0x20 or INT 0x21
                 // if yes, we've got a kernel call / ipc call
                 // else, cause minix SIGSEGV in process
                 let caller_endpoint = process_table.pid_to_endpoint(pid).unwrap(); // these unwraps should - in general - be safe, since the only children should be minix processes
+                // the instruction pointer may point outside of the process's memory,
+                // which is a regular segfault
                 let instruction = process_table
                     .get(caller_endpoint)
                     .unwrap()
                     .read_instruction()
-                    .unwrap();
+                    .unwrap_or(Instruction::Other);
 
                 match instruction {
                     Instruction::Int(0x20) => {
@@ -58,28 +97,40 @@ fn main_loop(process_table: &mut MinixProcessTable) -> Result<(), nix::Error> {
                         ipc::do_ipc(caller_endpoint, process_table).unwrap();
                     }
                     _ => {
-                        // other
-                        let _ = process_table
-                            .get(caller_endpoint)
-                            .unwrap()
-                            .cause_signal(SIGSEGV);
+                        // a real segfault
+                        sys::cause_linux_sig(caller_endpoint, SIGSEGV, process_table)?;
                     }
                 }
             }
+            WaitStatus::Stopped(pid, SIGSTOP) => {
+                // the process was stopped, so its signals can be handled.
+                // It's held stopped until the signal manager is done
+                if let Some(process) = process_table.get_by_pid(pid) {
+                    process.cont()?;
+                }
+            }
             WaitStatus::Stopped(pid, sig) => {
-                // received other signal than SIGSEGV
-                // TODO: cause a minix signal in process
-                // think about how to resume the process
-                // (probably will clear up after investigating
-                // how Minix handles signals)
-                let _ = process_table.get_by_pid(pid).unwrap().cause_signal(sig);
+                // received other signal than SIGSEGV,
+                // which is handled like a Minix signal
+                if let Some(endpoint) = process_table.pid_to_endpoint(pid) {
+                    sys::cause_linux_sig(endpoint, sig, process_table)?;
+                }
+            }
+            WaitStatus::Exited(pid, _) | WaitStatus::Signaled(pid, _, _) => {
+                // the process is gone: clean up after it,
+                // and keep running the remaining processes
+                if let Some(endpoint) = process_table.pid_to_endpoint(pid) {
+                    sys::clear_endpoint(endpoint, process_table)?;
+                }
+
+                if process_table.is_empty() {
+                    return Ok(());
+                }
             }
-            WaitStatus::Exited(_, _) => todo!("process exited normally"),
-            WaitStatus::Signaled(_, _, _) => panic!("process was killed by a (linux) signal. Problematic?"),
             WaitStatus::PtraceEvent(_, _, _) => unreachable!("probably unused and will be ignored"),
             WaitStatus::PtraceSyscall(_) => todo!("processes shouldn't call syscalls, so this should be ignored. Or kill process as misbehaving?"),
             WaitStatus::Continued(_) => unreachable!("WCONTINUED was not set, so this won't happen"),
-            WaitStatus::StillAlive => unreachable!("WNOHANG was not set, so this won't happen"),
+            WaitStatus::StillAlive => unreachable!("ChildWaiter doesn't return this"),
         }
     }
 }
@@ -87,26 +138,64 @@ fn main_loop(process_table: &mut MinixProcessTable) -> Result<(), nix::Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_utils::test_bin;
+    use utils::{
+        minix_errno::{EDEADSRCDST, ELOCKED},
+        Endpoint,
+    };
+
+    /// spawns a process with the privileges of a system
+    /// process, so that it may use all the ipc calls
+    fn spawn_system(path: &str, proc_nr: Endpoint) -> MinixProcess {
+        let mut process = MinixProcess::spawn(path).unwrap();
+        process.privileges = sys::Priv::system(sys::static_priv_id(proc_nr));
+        process
+    }
 
     #[test]
-    #[should_panic(expected = "not yet implemented: Handle invalid endpoints. Endpoint: 0")]
     fn send_receive_test() {
         let mut process_table = MinixProcessTable::new();
 
-        let _ = process_table.insert(MinixProcess::spawn("test_bin/sender_main").unwrap(), 41);
-        let _ = process_table.insert(MinixProcess::spawn("test_bin/receiver").unwrap(), 42);
+        let _ = process_table.insert(spawn_system(&test_bin("send_41"), 41), 41);
+        let _ = process_table.insert(spawn_system(&test_bin("receive_42"), 42), 42);
 
-        main_loop(&mut process_table).unwrap();
+        // the processes exchange messages and exit with the result of
+        // their exit call to the (absent) process manager
+        let statuses = test_utils::run_processes(&mut process_table);
+        let dead = EDEADSRCDST as u8 as i32;
+        assert_eq!(statuses, [(41, dead), (42, dead)]);
     }
 
     #[test]
-    #[should_panic(expected = "not yet implemented: Handle invalid endpoints. Endpoint: 0")]
     fn sendrec_test() {
         let mut process_table = MinixProcessTable::new();
 
-        let _ = process_table.insert(MinixProcess::spawn("test_bin/sendrec_39").unwrap(), 39);
-        let _ = process_table.insert(MinixProcess::spawn("test_bin/sendrec_40").unwrap(), 40);
+        let _ = process_table.insert(spawn_system(&test_bin("sendrec_39"), 39), 39);
+        let _ = process_table.insert(spawn_system(&test_bin("sendrec_40"), 40), 40);
+
+        // the processes exchange messages and exit with the result of
+        // their exit call to the (absent) process manager
+        let statuses = test_utils::run_processes(&mut process_table);
+        let dead = EDEADSRCDST as u8 as i32;
+        assert_eq!(statuses, [(39, dead), (40, dead)]);
+    }
+
+    #[test]
+    fn deadlock_test() {
+        let mut process_table = MinixProcessTable::new();
 
-        main_loop(&mut process_table).unwrap();
+        // the processes `sendrec` to each other. One of them
+        // should get ELOCKED, instead of both blocking forever
+        let _ = process_table.insert(spawn_system(&test_bin("sendrec_deadlock_50"), 50), 50);
+        let _ = process_table.insert(spawn_system(&test_bin("sendrec_deadlock_51"), 51), 51);
+
+        // the other one gets EDEADSRCDST when the first one exits. Which
+        // one is first depends on the order they're scheduled in
+        let statuses = test_utils::run_processes(&mut process_table);
+        let mut results: Vec<_> = statuses.iter().map(|&(_, status)| status).collect();
+        results.sort_unstable();
+        let mut expected = [ELOCKED as u8 as i32, EDEADSRCDST as u8 as i32];
+        expected.sort_unstable();
+        assert_eq!(results, expected);
     }
 }
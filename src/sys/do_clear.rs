@@ -0,0 +1,37 @@
+use crate::utils::{
+    minix_errno::{EINVAL, OK},
+    Endpoint, Message, MessagePayload, MinixProcessTable, Payload,
+};
+
+/// handles sys_clear: the process manager cleans up
+/// the kernel-side state of an exiting process
+pub fn do_clear(
+    _caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysClear = Payload::from_payload(&message.payload);
+
+    // don't clear if already cleared
+    if !process_table.isokendpt(message.endpt) {
+        println!("sys_clear: Invalid endpoint {}", message.endpt);
+        return Ok(EINVAL);
+    }
+
+    // remove the process' ability to send and receive messages,
+    // and release its slot in the process table
+    super::clear_endpoint(message.endpt, process_table)?;
+
+    Ok(OK)
+}
+
+/// the sys_clear() kernel call request message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysClear {
+    endpt: Endpoint,
+
+    padding: [u8; 52],
+}
+assert_eq_size!(MessageSysClear, MessagePayload);
+impl Payload for MessageSysClear {}
@@ -0,0 +1,31 @@
+use crate::sys::signal::MessageSigCalls;
+use crate::utils::{minix_errno::*, rts_flags, Endpoint, Message, MinixProcessTable, Payload};
+
+/// finishes the handling of the kernel signals of a process,
+/// resuming it if no new signals arrived in the meantime
+pub fn do_endksig(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSigCalls = Payload::from_payload(&message.payload);
+
+    if !process_table.isokendpt(message.endpt) {
+        return Ok(EINVAL);
+    }
+    let process = &mut process_table[message.endpt];
+    if process.privileges.s_sig_mgr != caller {
+        return Ok(EPERM);
+    }
+    if process.rts_flags & rts_flags::RTS_SIG_PENDING == 0 {
+        return Ok(EINVAL);
+    }
+
+    // the signal manager has finished one kernel signal. Is the process ready?
+    if process.rts_flags & rts_flags::RTS_SIGNALED == 0 {
+        process.rts_flags &= !rts_flags::RTS_SIG_PENDING;
+        process.resume_signaled()?;
+    }
+
+    Ok(OK)
+}
@@ -0,0 +1,68 @@
+use crate::sys::PROC_NAME_LEN;
+use crate::utils::{
+    minix_errno::{EINVAL, OK},
+    Endpoint, Message, MessagePayload, MinixProcessTable, Payload, ProcessState,
+};
+
+pub fn do_exec(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysExec = Payload::from_payload(&message.payload);
+
+    if !process_table.isokendpt(message.endpt) {
+        return Ok(EINVAL);
+    }
+
+    // save the command name, read from the caller's memory
+    let name = process_table[caller]
+        .read_buf_u8(message.name as u64, PROC_NAME_LEN - 1)
+        .ok()
+        .map(|buf| {
+            let len = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+            String::from_utf8_lossy(&buf[..len]).into_owned()
+        })
+        .unwrap_or_else(|| "<unset>".to_string());
+
+    // set the new process state: like in Minix, the general registers
+    // are cleared, and ebx points to the ps_strings struct
+    let process = &mut process_table[message.endpt];
+    let mut regs = process.get_regs()?;
+    regs.rax = 0;
+    regs.rcx = 0;
+    regs.rdx = 0;
+    regs.rsi = 0;
+    regs.rdi = 0;
+    regs.rbp = 0;
+    regs.rip = message.ip as u64;
+    regs.rsp = message.stack as u64;
+    regs.rbx = message.ps_str as u64;
+    process.set_regs(regs)?;
+    process.name = name;
+
+    // there's no reply to the exec call: unset the receiving
+    // state in the process and resume it
+    process.reply_pending = false;
+    if let ProcessState::Receiving(_) = process.state {
+        process.state = ProcessState::Running;
+        process.cont()?;
+    }
+
+    Ok(OK)
+}
+
+/// the sys_exec() kernel call request message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysExec {
+    endpt: Endpoint,
+    stack: u32,
+    name: u32,
+    ip: u32,
+    ps_str: u32,
+
+    padding: [u8; 36],
+}
+assert_eq_size!(MessageSysExec, MessagePayload);
+impl Payload for MessageSysExec {}
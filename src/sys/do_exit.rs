@@ -1,9 +1,16 @@
-use crate::utils::{Endpoint, Message, MinixProcessTable};
+use crate::utils::{minix_errno::EDONTREPLY, Endpoint, Message, MinixProcessTable};
 
+/// handles sys_exit: a system process has requested to exit
 pub fn do_exit(
-    _caller: Endpoint,
+    caller: Endpoint,
     _: Message,
-    _process_table: &mut MinixProcessTable,
+    process_table: &mut MinixProcessTable,
 ) -> Result<i32, nix::Error> {
-    unimplemented!();
+    // Minix generates a self-termination signal here, which is
+    // then handled by the process manager. We don't have that
+    // machinery, so the process is torn down immediately.
+    super::clear_endpoint(caller, process_table)?;
+
+    // the caller is gone, so there's no one to reply to
+    Ok(EDONTREPLY)
 }
@@ -0,0 +1,83 @@
+use crate::sys::Priv;
+use crate::utils::{
+    minix_errno::{EINVAL, OK},
+    priv_flags, Endpoint, Message, MessagePayload, MinixProcessTable, Payload,
+};
+
+pub fn do_fork(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysFork = Payload::from_payload(&message.payload);
+
+    // the parent has to exist, and the child's slot has to be free
+    let child_endpoint = match process_table.next_endpoint(message.slot) {
+        Some(endpoint) => endpoint,
+        None => return Ok(EINVAL),
+    };
+    if !process_table.isokendpt(message.endpt) || process_table.isokendpt(child_endpoint) {
+        return Ok(EINVAL);
+    }
+
+    let parent = &process_table[message.endpt];
+    let mut child = parent.fork()?;
+
+    // if the parent is a system process, the child
+    // doesn't inherit its privileges
+    if parent.s_flags & priv_flags::SYS_PROC != 0 {
+        child.s_flags = 0;
+        child.privileges = Priv::user();
+    }
+
+    // the child has no pending notifications, asynchronous messages or alarms
+    child.privileges.s_notify_pending = Default::default();
+    child.privileges.s_asyn_pending = Default::default();
+    child.privileges.s_alarm_timer.reset();
+    child.privileges.s_asyntab = (-1i32) as u32;
+    child.privileges.s_asynsize = 0;
+
+    let msgaddr = parent.delivermsg_vir as u32;
+    process_table.insert(child, child_endpoint).unwrap();
+
+    // write the response to the caller's message
+    // (+8, since we skip the source and type fields)
+    let response = MessageSysForkReply {
+        endpt: child_endpoint,
+        msgaddr,
+        padding: [0; 48],
+    };
+    let caller = &process_table[caller];
+    let regs = caller.get_regs()?;
+    let data: [u32; 14] = response.into_payload();
+    let data_u64: [u64; 7] = unsafe { std::mem::transmute(data) };
+    caller.write_buf(regs.rax + 8, &data_u64)?;
+
+    Ok(OK)
+}
+
+/// the sys_fork() kernel call request message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysFork {
+    endpt: Endpoint,
+    slot: Endpoint,
+    flags: u32,
+    msgaddr: u32,
+
+    padding: [u8; 40],
+}
+assert_eq_size!(MessageSysFork, MessagePayload);
+impl Payload for MessageSysFork {}
+
+/// response to the sys_fork() kernel call
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysForkReply {
+    endpt: Endpoint,
+    msgaddr: u32,
+
+    padding: [u8; 48],
+}
+assert_eq_size!(MessageSysForkReply, MessagePayload);
+impl Payload for MessageSysForkReply {}
@@ -0,0 +1,45 @@
+use crate::sys::signal::MessageSigCalls;
+use crate::utils::{
+    endpoint::NONE, minix_errno::*, rts_flags, Endpoint, Message, MinixProcessTable, Payload,
+};
+
+/// finds the next process with kernel signals pending
+/// for the calling signal manager, and returns its signals
+pub fn do_getksig(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSigCalls = Payload::from_payload(&message.payload);
+
+    let signaled = process_table.endpoints().find(|&endpoint| {
+        let process = &process_table[endpoint];
+        process.rts_flags & rts_flags::RTS_SIGNALED != 0 && process.privileges.s_sig_mgr == caller
+    });
+
+    let response = match signaled {
+        Some(endpoint) => {
+            let process = &mut process_table[endpoint];
+            // the process stays stopped, until SYS_ENDKSIG
+            process.rts_flags &= !rts_flags::RTS_SIGNALED;
+            MessageSigCalls {
+                endpt: endpoint,
+                map: std::mem::take(&mut process.pending_signals),
+                ..message
+            }
+        }
+        // no process with pending signals was found
+        None => MessageSigCalls {
+            endpt: NONE,
+            ..message
+        },
+    };
+
+    let caller = &process_table[caller];
+    let regs = caller.get_regs()?;
+    let data: [u32; 14] = response.into_payload();
+    let data_u64: [u64; 7] = unsafe { std::mem::transmute(data) };
+    caller.write_buf(regs.rax + 8, &data_u64)?;
+
+    Ok(OK)
+}
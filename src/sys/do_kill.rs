@@ -0,0 +1,61 @@
+use nix::sys::{signal::Signal, wait::WaitStatus};
+
+use crate::sys::signal::{MessageSigCalls, NSIG};
+use crate::sys::{cause_linux_sig, cause_sig, clear_endpoint};
+use crate::utils::{
+    endpoint::{endpoint_p, iskerneln},
+    minix_errno::*,
+    rts_flags, Endpoint, Message, MinixProcessTable, Payload, ProcessState,
+};
+
+/// sends a signal to a process, on behalf of the signal manager
+/// (usually PM). The signal is handled by the signal manager
+/// of the target, which collects it with SYS_GETKSIG
+pub fn do_kill(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSigCalls = Payload::from_payload(&message.payload);
+    let target = message.endpt;
+
+    if !process_table.isokendpt(target) || message.sig <= 0 || message.sig >= NSIG {
+        return Ok(EINVAL);
+    }
+    if iskerneln(endpoint_p(target)) {
+        return Ok(EPERM);
+    }
+
+    // a target with signals still pending is already held stopped
+    let was_held = process_table[target].rts_flags & rts_flags::RTS_SIG_PENDING != 0
+        || process_table[target].is_signal_stopped();
+
+    // set pending signal to be processed by the signal manager
+    cause_sig(target, message.sig, process_table)?;
+
+    let process = &process_table[target];
+    if target == caller {
+        // the caller doesn't get a reply, but resumes
+        // once its signals are handled
+        process.cont()?;
+        return Ok(EDONTREPLY);
+    }
+
+    // a running process has to be stopped, until
+    // the signal manager is done with its signals
+    if let ProcessState::Running = process.state {
+        if !was_held && process.rts_flags & rts_flags::RTS_SIG_PENDING != 0 {
+            match process.stop()? {
+                // an interrupted kernel or ipc call (or a segfault) is
+                // repeated when the process resumes, since the faulting
+                // instruction wasn't skipped yet
+                WaitStatus::Stopped(_, Signal::SIGSTOP | Signal::SIGSEGV) => process.cont()?,
+                WaitStatus::Stopped(_, sig) => cause_linux_sig(target, sig, process_table)?,
+                // the process exited before it stopped
+                _ => clear_endpoint(target, process_table)?,
+            }
+        }
+    }
+
+    Ok(OK)
+}
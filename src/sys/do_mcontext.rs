@@ -0,0 +1,176 @@
+use std::mem::size_of;
+
+use crate::sys::signal::{fpu_init_state, FPU_XFP_SIZE, X86_FLAGS_USER};
+use crate::utils::{
+    endpoint::{endpoint_p, iskerneln},
+    minix_errno::*,
+    Endpoint, Message, MessagePayload, MinixProcess, MinixProcessTable, Payload,
+};
+
+/// reads the machine context of a process (its registers,
+/// including the FPU state) into the `mcontext_t` in its memory.
+/// Used by PM to implement `getcontext()`
+pub fn do_getmcontext(
+    _caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysMcontext = Payload::from_payload(&message.payload);
+
+    if !process_table.isokendpt(message.endpt) {
+        return Ok(EINVAL);
+    }
+    if iskerneln(endpoint_p(message.endpt)) {
+        return Ok(EPERM);
+    }
+
+    // get the mcontext structure, so the other fields are kept
+    let process = &process_table[message.endpt];
+    let mut mc = read_mcontext(message.ctx_ptr as u64, process)?;
+
+    let regs = process.get_regs()?;
+    mc.gregs[reg::GS] = regs.gs as i32;
+    mc.gregs[reg::FS] = regs.fs as i32;
+    mc.gregs[reg::ES] = regs.es as i32;
+    mc.gregs[reg::DS] = regs.ds as i32;
+    mc.gregs[reg::EDI] = regs.rdi as i32;
+    mc.gregs[reg::ESI] = regs.rsi as i32;
+    mc.gregs[reg::EBP] = regs.rbp as i32;
+    mc.gregs[reg::ESP] = regs.rsp as i32;
+    mc.gregs[reg::EBX] = regs.rbx as i32;
+    mc.gregs[reg::EDX] = regs.rdx as i32;
+    mc.gregs[reg::ECX] = regs.rcx as i32;
+    mc.gregs[reg::EAX] = regs.rax as i32;
+    mc.gregs[reg::EIP] = regs.rip as i32;
+    mc.gregs[reg::CS] = regs.cs as i32;
+    mc.gregs[reg::EFL] = regs.eflags as i32;
+    mc.gregs[reg::UESP] = regs.rsp as i32;
+    mc.gregs[reg::SS] = regs.ss as i32;
+
+    // copy the FPU state
+    mc.fp_reg_set = process.get_fpregs()?;
+    mc.mc_flags = MC_FPU_SAVED;
+
+    // copy the mcontext structure to the process's memory
+    write_mcontext(mc, message.ctx_ptr as u64, process)?;
+
+    Ok(OK)
+}
+
+/// sets the machine context of a process from the `mcontext_t`
+/// in its memory. Used by PM to implement `setcontext()`
+pub fn do_setmcontext(
+    _caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysMcontext = Payload::from_payload(&message.payload);
+
+    if !process_table.isokendpt(message.endpt) {
+        return Ok(EINVAL);
+    }
+    if iskerneln(endpoint_p(message.endpt)) {
+        return Ok(EPERM);
+    }
+
+    // get the mcontext structure
+    let process = &process_table[message.endpt];
+    let mc = read_mcontext(message.ctx_ptr as u64, process)?;
+
+    // restore user bits of eflags from mc, maintain system bits
+    let mut regs = process.get_regs()?;
+    let eflags =
+        (mc.gregs[reg::EFL] as u32 & X86_FLAGS_USER) | (regs.eflags as u32 & !X86_FLAGS_USER);
+
+    // the segment registers aren't restored
+    regs.rdi = mc.gregs[reg::EDI] as u32 as u64;
+    regs.rsi = mc.gregs[reg::ESI] as u32 as u64;
+    regs.rbp = mc.gregs[reg::EBP] as u32 as u64;
+    regs.rsp = mc.gregs[reg::ESP] as u32 as u64;
+    regs.rbx = mc.gregs[reg::EBX] as u32 as u64;
+    regs.rdx = mc.gregs[reg::EDX] as u32 as u64;
+    regs.rcx = mc.gregs[reg::ECX] as u32 as u64;
+    regs.rax = mc.gregs[reg::EAX] as u32 as u64;
+    regs.rip = mc.gregs[reg::EIP] as u32 as u64;
+    regs.eflags = eflags as u64;
+
+    // the ipc calls in progress don't change the registers anymore
+    process.set_context(regs)?;
+
+    // copy the FPU state, or start with a clean FPU if there's none
+    if mc.mc_flags & MC_FPU_SAVED != 0 {
+        process.set_fpregs(&mc.fp_reg_set)?;
+    } else {
+        process.set_fpregs(&fpu_init_state())?;
+    }
+
+    Ok(OK)
+}
+
+fn read_mcontext(addr: u64, process: &MinixProcess) -> Result<McontextT, nix::Error> {
+    let buf = process.read_buf_u8(addr, size_of::<McontextT>())?;
+
+    let mut result = [0u8; size_of::<McontextT>()];
+    result.copy_from_slice(&buf);
+
+    Ok(unsafe { std::mem::transmute::<[u8; size_of::<McontextT>()], McontextT>(result) })
+}
+
+fn write_mcontext(mc: McontextT, addr: u64, process: &MinixProcess) -> Result<(), nix::Error> {
+    let data: [u8; size_of::<McontextT>()] = unsafe { std::mem::transmute(mc) };
+    process.write_buf_u8(addr, &data)
+}
+
+/// set in `mc_flags` if the FPU state in the mcontext is valid
+const MC_FPU_SAVED: i32 = 0x001;
+
+/// the indices of the registers in `gregs`,
+/// defined in `sys/arch/i386/include/mcontext.h`
+#[allow(dead_code)]
+mod reg {
+    pub const GS: usize = 0;
+    pub const FS: usize = 1;
+    pub const ES: usize = 2;
+    pub const DS: usize = 3;
+    pub const EDI: usize = 4;
+    pub const ESI: usize = 5;
+    pub const EBP: usize = 6;
+    pub const ESP: usize = 7;
+    pub const EBX: usize = 8;
+    pub const EDX: usize = 9;
+    pub const ECX: usize = 10;
+    pub const EAX: usize = 11;
+    pub const TRAPNO: usize = 12;
+    pub const ERR: usize = 13;
+    pub const EIP: usize = 14;
+    pub const CS: usize = 15;
+    pub const EFL: usize = 16;
+    pub const UESP: usize = 17;
+    pub const SS: usize = 18;
+
+    pub const NGREG: usize = 19;
+}
+
+/// the machine context of a process, defined in `sys/arch/i386/include/mcontext.h`
+#[repr(C)]
+struct McontextT {
+    gregs: [i32; reg::NGREG],
+    fp_reg_set: [u8; FPU_XFP_SIZE], // x87 and xmm regs in fxsave format
+    fp_pad: [i32; 33],              // historic padding
+    mc_tlsbase: i32,
+    mc_flags: i32,
+    mc_magic: i32,
+}
+assert_eq_size!(McontextT, [u8; 732]);
+
+/// the sys_getmcontext() and sys_setmcontext() kernel call message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysMcontext {
+    endpt: Endpoint, // the process whose context is read or set
+    ctx_ptr: u32,    // the address of the mcontext_t, in the process's memory
+
+    padding: [u8; 48],
+}
+assert_eq_size!(MessageSysMcontext, MessagePayload);
+impl Payload for MessageSysMcontext {}
@@ -0,0 +1,73 @@
+use crate::clock_task::{reset_kernel_timer, set_kernel_timer};
+use crate::sys::{tmr_is_first, TMR_NEVER};
+use crate::utils::{
+    minix_errno::*, priv_flags, Endpoint, Message, MessagePayload, MinixProcessTable, Payload,
+};
+
+/// requests a synchronous alarm, or cancels the alarm
+/// of the caller. The alarm is delivered as a notification from CLOCK
+pub fn do_setalarm(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysSetAlarm = Payload::from_payload(&message.payload);
+
+    if process_table[caller].s_flags & priv_flags::SYS_PROC == 0 {
+        return Ok(EPERM);
+    }
+
+    // the clock ticks passed to the processes are 32-bit
+    let uptime = process_table.clock.uptime() as u32;
+
+    // return the ticks left on the previous alarm
+    let timer = &process_table[caller].privileges.s_alarm_timer;
+    let time_left = if !timer.is_set() {
+        TMR_NEVER
+    } else if tmr_is_first(uptime, timer.exp_time()) {
+        timer.exp_time().wrapping_sub(uptime)
+    } else {
+        0
+    };
+
+    // (re)set the timer, depending on the expiration time.
+    // An absolute time of 0 is in the past, so that alarm expires right away
+    if message.abs_time == 0 && message.exp_time == 0 {
+        reset_kernel_timer(caller, process_table);
+    } else {
+        let exp_time = if message.abs_time != 0 {
+            message.exp_time
+        } else {
+            message.exp_time.wrapping_add(uptime)
+        };
+        set_kernel_timer(caller, exp_time, process_table);
+    }
+
+    // for the caller's convenience, also return the current time
+    let response = MessageSysSetAlarm {
+        time_left,
+        uptime,
+        ..message
+    };
+    let caller = &process_table[caller];
+    let regs = caller.get_regs()?;
+    let data: [u32; 14] = response.into_payload();
+    let data_u64: [u64; 7] = unsafe { std::mem::transmute(data) };
+    caller.write_buf(regs.rax + 8, &data_u64)?;
+
+    Ok(OK)
+}
+
+/// the sys_setalarm() kernel call message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysSetAlarm {
+    exp_time: u32,  // expiration time, in clock ticks
+    time_left: u32, // ticks left on the previous alarm
+    uptime: u32,    // the current uptime
+    abs_time: i32,  // is `exp_time` absolute, or relative to the current time?
+
+    padding: [u8; 40],
+}
+assert_eq_size!(MessageSysSetAlarm, MessagePayload);
+impl Payload for MessageSysSetAlarm {}
@@ -0,0 +1,69 @@
+use crate::utils::{
+    minix_errno::*, ticks, Endpoint, Message, MessagePayload, MinixProcessTable, Payload,
+};
+
+/// the only clock that can be set
+const CLOCK_REALTIME: i32 = 0;
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// sets the real time of the (virtual) wall clock, or gradually
+/// adjusts it like `adjtime()`. The host's clock is never changed
+pub fn do_settime(
+    _caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysSettime = Payload::from_payload(&message.payload);
+    let clock = &process_table.clock;
+
+    // only realtime can change
+    if message.clock_id != CLOCK_REALTIME {
+        return Ok(EINVAL);
+    }
+
+    // the user just wants to adjtime(),
+    // by seconds and nanoseconds either of which may be negative
+    if message.now == 0 {
+        let nanos = message.sec as i128 * NANOS_PER_SEC + message.nsec as i128;
+        let delta = ticks(nanos.unsigned_abs()) as i64 * nanos.signum() as i64;
+        clock.set_adjtime_delta(delta);
+        return Ok(OK);
+    }
+
+    // else the user wants to set the time
+    if message.nsec < 0 || message.nsec as i128 >= NANOS_PER_SEC {
+        return Ok(EINVAL);
+    }
+    let hz = crate::HZ as i64;
+    let nsec_ticks = ticks(message.nsec as u128) as i64;
+    let boottime = clock.boottime() as i64;
+    let timediff_ticks = (message.sec - boottime).saturating_mul(hz);
+
+    // prevent a negative value for realtime, and keep it
+    // in the range of the (32-bit) clock ticks of the processes
+    if message.sec <= boottime || timediff_ticks > u32::MAX as i64 {
+        // boottime was likely wrong, try to correct it
+        clock.set_boottime(message.sec as u64);
+        clock.set_realtime(1);
+        return Ok(OK);
+    }
+
+    clock.set_realtime((timediff_ticks + nsec_ticks) as u64);
+
+    Ok(OK)
+}
+
+/// the sys_settime() kernel call request message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysSettime {
+    now: i32,      // set the time now, instead of adjusting it
+    clock_id: i32, // : clockid_t
+    sec: i64,      // : time_t
+    nsec: i32,     // : long
+
+    padding: [u8; 36],
+}
+assert_eq_size!(MessageSysSettime, MessagePayload);
+impl Payload for MessageSysSettime {}
@@ -0,0 +1,64 @@
+use std::mem::size_of;
+
+use crate::sys::signal::{MessageSigCalls, SigContext, MF_FPU_INITIALIZED, X86_FLAGS_USER};
+use crate::utils::{
+    endpoint::{endpoint_p, iskerneln},
+    minix_errno::*,
+    Endpoint, Message, MinixProcess, MinixProcessTable, Payload,
+};
+
+/// restores the registers of a process, saved in the sigcontext
+/// by `do_sigsend`, when it returns from a signal handler
+pub fn do_sigreturn(
+    _caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSigCalls = Payload::from_payload(&message.payload);
+
+    if !process_table.isokendpt(message.endpt) {
+        return Ok(EINVAL);
+    }
+    if iskerneln(endpoint_p(message.endpt)) {
+        return Ok(EPERM);
+    }
+
+    // copy in the sigcontext structure
+    let process = &process_table[message.endpt];
+    let sc = read_sigcontext(message.sigctx as u64, process)?;
+
+    // restore user bits of eflags from sc, maintain system bits
+    let mut regs = process.get_regs()?;
+    let eflags = (sc.sc_eflags as u32 & X86_FLAGS_USER) | (regs.eflags as u32 & !X86_FLAGS_USER);
+
+    // write back registers we allow to be restored,
+    // i.e. not the segment ones
+    regs.rdi = sc.sc_edi as u32 as u64;
+    regs.rsi = sc.sc_esi as u32 as u64;
+    regs.rbp = sc.sc_ebp as u32 as u64;
+    regs.rbx = sc.sc_ebx as u32 as u64;
+    regs.rdx = sc.sc_edx as u32 as u64;
+    regs.rcx = sc.sc_ecx as u32 as u64;
+    regs.rax = sc.sc_eax as u32 as u64;
+    regs.rip = sc.sc_eip as u32 as u64;
+    regs.eflags = eflags as u64;
+    regs.rsp = sc.sc_esp as u32 as u64;
+
+    // the ipc calls in progress don't change the registers anymore
+    process.set_context(regs)?;
+
+    if sc.sc_flags & MF_FPU_INITIALIZED != 0 {
+        process.set_fpregs(&sc.sc_fpu_state)?;
+    }
+
+    Ok(OK)
+}
+
+fn read_sigcontext(addr: u64, process: &MinixProcess) -> Result<SigContext, nix::Error> {
+    let buf = process.read_buf_u8(addr, size_of::<SigContext>())?;
+
+    let mut result = [0u8; size_of::<SigContext>()];
+    result.copy_from_slice(&buf);
+
+    Ok(unsafe { std::mem::transmute::<[u8; size_of::<SigContext>()], SigContext>(result) })
+}
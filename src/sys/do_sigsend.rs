@@ -0,0 +1,126 @@
+use std::mem::size_of;
+
+use crate::sys::signal::{
+    fpu_init_state, MessageSigCalls, SigContext, SigMsg, SigframeSigcontext, MF_FPU_INITIALIZED,
+    SC_MAGIC,
+};
+use crate::utils::{
+    endpoint::{endpoint_p, iskerneln},
+    minix_errno::*,
+    Endpoint, Message, MinixProcess, MinixProcessTable, Payload, ProcessState,
+};
+
+/// builds a signal frame on the stack of a process, and
+/// makes the process run the signal handler when it resumes.
+/// The frame holds the registers `do_sigreturn` restores
+pub fn do_sigsend(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSigCalls = Payload::from_payload(&message.payload);
+
+    if !process_table.isokendpt(message.endpt) {
+        return Ok(EINVAL);
+    }
+    if iskerneln(endpoint_p(message.endpt)) {
+        return Ok(EPERM);
+    }
+
+    // get the sigmsg structure from the caller
+    let smsg = read_sigmsg(message.sigctx as u64, &process_table[caller])?;
+
+    // the registers can only be changed while the process is stopped
+    let process = &process_table[message.endpt];
+    if let ProcessState::Running = process.state {
+        if message.endpt != caller && !process.is_signal_stopped() {
+            println!("do_sigsend: sigsend a running process");
+            return Ok(EINVAL);
+        }
+    }
+
+    // a process blocked in an ipc call is unblocked, so it runs the handler
+    // right away. The ipc call returns EINTR once the handler returns
+    let interrupted = !matches!(process.state, ProcessState::Running);
+    if let ProcessState::Sending(dst) | ProcessState::SendReceiving(dst) = process.state {
+        if let Some(receiver) = process_table.get_mut(dst) {
+            receiver.queue.remove(message.endpt);
+        }
+    }
+    let process = &mut process_table[message.endpt];
+    process.state = ProcessState::Running;
+    process.reply_pending = false;
+    let mut regs = process.get_regs()?;
+
+    // compute the user stack pointer where the sigframe will start
+    let frp = (regs.rsp as u32).wrapping_sub(size_of::<SigframeSigcontext>() as u32);
+    let scp = frp + 7 * 4; // the address of `sf_sc`
+
+    // copy the registers to the sigcontext structure
+    let mut sc: SigContext = unsafe { std::mem::transmute([0u8; size_of::<SigContext>()]) };
+    sc.sc_gs = regs.gs as i32;
+    sc.sc_fs = regs.fs as i32;
+    sc.sc_es = regs.es as i32;
+    sc.sc_ds = regs.ds as i32;
+    sc.sc_edi = regs.rdi as i32;
+    sc.sc_esi = regs.rsi as i32;
+    sc.sc_ebp = regs.rbp as i32;
+    sc.sc_ebx = regs.rbx as i32;
+    sc.sc_edx = regs.rdx as i32;
+    sc.sc_ecx = regs.rcx as i32;
+    sc.sc_eax = if interrupted { EINTR } else { regs.rax as i32 };
+    sc.sc_eip = regs.rip as i32;
+    sc.sc_cs = regs.cs as i32;
+    sc.sc_eflags = regs.eflags as i32;
+    sc.sc_esp = regs.rsp as i32;
+    sc.sc_ss = regs.ss as i32;
+
+    // save the FPU context to the sigcontext
+    sc.sc_fpu_state = process.get_fpregs()?;
+
+    // finish the sigcontext initialization
+    sc.sc_mask = smsg.sm_mask;
+    sc.sc_flags = MF_FPU_INITIALIZED;
+    sc.sc_magic = SC_MAGIC;
+
+    // the handler returns to sigreturn() in the C library,
+    // which gets the sigcontext as its argument
+    let frame = SigframeSigcontext {
+        sf_ra_sigreturn: smsg.sm_sigreturn,
+        sf_signum: smsg.sm_signo,
+        sf_code: 0,
+        sf_scp: scp,
+        sf_fp: regs.rbp as u32,
+        sf_ra: regs.rip as u32,
+        sf_scpcopy: scp,
+        sf_sc: sc,
+    };
+
+    // copy the sigframe structure to the user's stack
+    let data: [u8; size_of::<SigframeSigcontext>()] = unsafe { std::mem::transmute(frame) };
+    process.write_buf_u8(frp as u64, &data)?;
+
+    // reset user registers to execute the signal handler
+    regs.rsp = frp as u64;
+    regs.rip = smsg.sm_sighandler as u64;
+    regs.rbp = (frp + 4 * 4) as u64; // the address of `sf_fp`
+    process.set_regs(regs)?;
+
+    // signal handler should get clean FPU
+    process.set_fpregs(&fpu_init_state())?;
+
+    if interrupted {
+        process.cont()?;
+    }
+
+    Ok(OK)
+}
+
+fn read_sigmsg(addr: u64, process: &MinixProcess) -> Result<SigMsg, nix::Error> {
+    let buf = process.read_buf_u8(addr, size_of::<SigMsg>())?;
+
+    let mut result = [0u8; size_of::<SigMsg>()];
+    result.copy_from_slice(&buf);
+
+    Ok(unsafe { std::mem::transmute::<[u8; size_of::<SigMsg>()], SigMsg>(result) })
+}
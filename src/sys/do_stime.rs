@@ -0,0 +1,25 @@
+use crate::utils::{minix_errno::*, Endpoint, Message, MessagePayload, MinixProcessTable, Payload};
+
+/// sets the boot time of the (virtual) wall clock
+pub fn do_stime(
+    _caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysStime = Payload::from_payload(&message.payload);
+
+    process_table.clock.set_boottime(message.boot_time as u64);
+
+    Ok(OK)
+}
+
+/// the sys_stime() kernel call request message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysStime {
+    boot_time: i64, // : time_t
+
+    padding: [u8; 48],
+}
+assert_eq_size!(MessageSysStime, MessagePayload);
+impl Payload for MessageSysStime {}
@@ -0,0 +1,75 @@
+use crate::utils::{
+    endpoint::SELF, minix_errno::*, Endpoint, Message, MessagePayload, MinixProcessTable, Payload,
+};
+
+/// returns the accounting times of a process,
+/// with the current uptime, real time and boot time
+pub fn do_times(
+    caller: Endpoint,
+    message: Message,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    let message: MessageSysTimes = Payload::from_payload(&message.payload);
+
+    let endpoint = if message.endpt == SELF {
+        caller
+    } else {
+        message.endpt
+    };
+
+    // the times of a process are only returned if it exists
+    // (which `NONE` never does). A process which is still in
+    // the table may be gone already, and has no times either
+    let (user_time, system_time) = match process_table.get(endpoint) {
+        Some(process) => process.cpu_times().unwrap_or((0, 0)),
+        None => (0, 0),
+    };
+
+    // the clock ticks passed to the processes are 32-bit
+    let clock = &process_table.clock;
+    let (boottime, realtime) = clock.wall_time();
+    let response = MessageSysTimesReply {
+        real_ticks: realtime as u32,
+        boot_ticks: clock.uptime() as u32,
+        user_time: user_time as u32,
+        system_time: system_time as u32,
+        boot_time: boottime as i64,
+        padding: [0; 32],
+    };
+
+    // write the response to the caller's message
+    // (+8, since we skip the source and type fields)
+    let caller = &process_table[caller];
+    let regs = caller.get_regs()?;
+    let data: [u32; 14] = response.into_payload();
+    let data_u64: [u64; 7] = unsafe { std::mem::transmute(data) };
+    caller.write_buf(regs.rax + 8, &data_u64)?;
+
+    Ok(OK)
+}
+
+/// the sys_times() kernel call request message
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysTimes {
+    endpt: Endpoint,
+
+    padding: [u8; 52],
+}
+assert_eq_size!(MessageSysTimes, MessagePayload);
+impl Payload for MessageSysTimes {}
+
+/// response to the sys_times() kernel call
+#[repr(C)]
+#[derive(Debug)]
+struct MessageSysTimesReply {
+    real_ticks: u32,  // real time in clock ticks since boot
+    boot_ticks: u32,  // number of clock ticks since boot
+    user_time: u32,   // user time in clock ticks
+    system_time: u32, // system time in clock ticks
+    boot_time: i64,   // : time_t; number of seconds since UNIX epoch, at boot
+
+    padding: [u8; 32],
+}
+assert_eq_size!(MessageSysTimesReply, MessagePayload);
+impl Payload for MessageSysTimesReply {}
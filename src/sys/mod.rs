@@ -3,14 +3,31 @@ mod types;
 #[allow(unused_imports)]
 pub use types::*;
 
+mod do_clear;
 mod do_diagctl;
+mod do_endksig;
+mod do_exec;
 mod do_exit;
+mod do_fork;
 mod do_getinfo;
+mod do_getksig;
+mod do_kill;
+mod do_mcontext;
+mod do_setalarm;
 mod do_setgrant;
+mod do_settime;
+mod do_sigreturn;
+mod do_sigsend;
 mod do_statectl;
+mod do_stime;
+mod do_times;
 
-use crate::utils::MinixProcessTable;
-use crate::utils::{Endpoint, Message};
+use nix::sys::signal::Signal;
+
+use crate::utils::minix_errno::{EBADREQUEST, ECALLDENIED, EDEADSRCDST, EDONTREPLY, EINVAL, OK};
+use crate::utils::{endpoint, rts_flags, Endpoint, Message};
+use crate::utils::{MinixProcessTable, ProcessState};
+use signal::{sigs_is_lethal, SIGKSIG, SIGKSIGSM};
 
 /// performs the kernel call for the calling process
 pub fn do_kernel_call(
@@ -32,13 +49,23 @@ pub fn do_kernel_call(
 
     // kernel call number is sent in the
     // m_type field of the message
-    let call_nr = message.m_type as usize;
-    let result;
+    let call_nr = (message.m_type as usize).wrapping_sub(KERNEL_CALL);
 
-    if (KERNEL_CALL..(KERNEL_CALL + NR_SYS_CALLS)).contains(&call_nr) {
-        result = CALL_VEC[call_nr - KERNEL_CALL](caller_endpoint, message, process_table)?;
+    let result = if call_nr >= NR_SYS_CALLS {
+        EBADREQUEST
+    } else if !process.privileges.may_call(call_nr) {
+        println!(
+            "SYSTEM: denied request {} from {}",
+            call_nr, caller_endpoint
+        );
+        ECALLDENIED
     } else {
-        unimplemented!()
+        CALL_VEC[call_nr](caller_endpoint, message, process_table)?
+    };
+
+    // the caller doesn't expect a reply (and may not exist anymore)
+    if result == EDONTREPLY {
+        return Ok(());
     }
 
     // TODO: maybe pass `&mut process` to the kernel call instead of doing this?
@@ -57,6 +84,160 @@ pub fn do_kernel_call(
     Ok(())
 }
 
+/// removes the process from the process table,
+/// and cleans up all the ipc state related to it.
+/// In Minix, this is implemented in kernel/system.c
+pub fn clear_endpoint(
+    endpoint: Endpoint,
+    process_table: &mut MinixProcessTable,
+) -> Result<(), nix::Error> {
+    let process = match process_table.remove(endpoint) {
+        Some(process) => process,
+        None => return Ok(()),
+    };
+
+    // if the process was queued trying to send a message,
+    // remove the message from the receiver's queue
+    if let ProcessState::Sending(dst) | ProcessState::SendReceiving(dst) = process.state {
+        if let Some(receiver) = process_table.get_mut(dst) {
+            receiver.queue.remove(endpoint);
+        }
+    }
+
+    // alert the processes sending to or receiving
+    // from the exiting process that it's no longer alive
+    let id = process.privileges.s_id as usize;
+    let endpoints: Vec<Endpoint> = process_table.endpoints().collect();
+    for other_endpoint in endpoints {
+        let other = &mut process_table[other_endpoint];
+
+        // unset pending notifications and asynchronous messages. User
+        // processes share their privilege id, and can't have any pending
+        if id != USER_PRIV_ID as usize {
+            other.privileges.s_notify_pending.unset(id);
+            other.privileges.s_asyn_pending.unset(id);
+        }
+
+        // check if the process depends on the exiting process
+        match other.state {
+            ProcessState::Sending(e)
+            | ProcessState::Receiving(e)
+            | ProcessState::SendReceiving(e)
+                if e == endpoint =>
+            {
+                println!(
+                    "endpoint {} / {} blocked on dead src ep {} / {}",
+                    other_endpoint, other.name, endpoint, process.name
+                );
+                crate::ipc::set_return_value(other, EDEADSRCDST)?;
+                other.state = ProcessState::Running;
+                other.reply_pending = false;
+                other.cont()?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// notifies the system process about a pending system signal.
+/// In Minix, this is implemented in kernel/system.c
+pub fn send_sig(
+    endpoint: Endpoint,
+    sig: i32,
+    process_table: &mut MinixProcessTable,
+) -> Result<i32, nix::Error> {
+    if !process_table.isokendpt(endpoint) {
+        return Ok(EINVAL);
+    }
+
+    process_table[endpoint].privileges.s_sig_pending.add(sig);
+    crate::ipc::do_notify(endpoint::SYSTEM, endpoint, process_table)?;
+
+    Ok(OK)
+}
+
+/// marks the signal pending for the process, and lets its
+/// signal manager know about it. The process isn't resumed
+/// until the signal manager is done with it (see `do_endksig`).
+/// In Minix, this is implemented in kernel/system.c
+pub fn cause_sig(
+    endpoint: Endpoint,
+    sig: i32,
+    process_table: &mut MinixProcessTable,
+) -> Result<(), nix::Error> {
+    let process = &mut process_table[endpoint];
+    let sig_mgr = match process.privileges.s_sig_mgr {
+        endpoint::SELF => endpoint,
+        sig_mgr => sig_mgr,
+    };
+
+    // if the target is the signal manager of itself, send the signal directly
+    if sig_mgr == endpoint {
+        if sigs_is_lethal(sig) {
+            // if the signal is lethal, see if a backup signal manager exists
+            let bak_sig_mgr = process.privileges.s_bak_sig_mgr;
+            if bak_sig_mgr != endpoint::NONE && process_table.isokendpt(bak_sig_mgr) {
+                let process = &mut process_table[endpoint];
+                process.privileges.s_sig_mgr = bak_sig_mgr;
+                process.privileges.s_bak_sig_mgr = endpoint::NONE;
+                // try again with the new signal manager
+                return cause_sig(endpoint, sig, process_table);
+            }
+
+            panic!(
+                "cause_sig: sig manager {} gets lethal signal {} for itself",
+                endpoint, sig
+            );
+        }
+
+        process.privileges.s_sig_pending.add(sig);
+        if send_sig(endpoint, SIGKSIGSM, process_table)? != OK {
+            panic!("send_sig failed");
+        }
+        return Ok(());
+    }
+
+    // check if the signal is already pending. Process it otherwise
+    if !process.pending_signals.is_member(sig) {
+        process.pending_signals.add(sig);
+        if process.rts_flags & rts_flags::RTS_SIGNALED == 0 {
+            process.rts_flags |= rts_flags::RTS_SIGNALED | rts_flags::RTS_SIG_PENDING;
+            if send_sig(sig_mgr, SIGKSIG, process_table)? != OK {
+                panic!("send_sig failed");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// handles a Linux signal the (stopped) process received, by causing
+/// the corresponding Minix signal, and resuming the process. Without
+/// a signal manager to handle it, the signal has its default Linux effect
+pub fn cause_linux_sig(
+    endpoint: Endpoint,
+    sig: Signal,
+    process_table: &mut MinixProcessTable,
+) -> Result<(), nix::Error> {
+    let process = &process_table[endpoint];
+    let sig_mgr = match process.privileges.s_sig_mgr {
+        endpoint::SELF => endpoint,
+        sig_mgr => sig_mgr,
+    };
+    if !process_table.isokendpt(sig_mgr) {
+        return process.cause_signal(sig);
+    }
+
+    // the process stays stopped if the signal is pending,
+    // and the Linux signal itself is discarded
+    if let Some(sig) = signal::from_linux_signal(sig) {
+        cause_sig(endpoint, sig, process_table)?;
+    }
+    process_table[endpoint].cont()
+}
+
 // the kernel call numbers are defined in `include/minix/com.h`
 const KERNEL_CALL: usize = 0x600;
 const NR_SYS_CALLS: usize = 58;
@@ -64,64 +245,64 @@ const NR_SYS_CALLS: usize = 58;
 type KernelCall = fn(Endpoint, Message, &mut MinixProcessTable) -> Result<i32, nix::Error>;
 
 const CALL_VEC: [KernelCall; NR_SYS_CALLS] = [
-    sys_unimplemented,        // 0
-    sys_unimplemented,        // 1
-    sys_unimplemented,        // 2
-    sys_unimplemented,        // 3
-    sys_unimplemented,        // 4 SYS_PRIVCTL
-    sys_unimplemented,        // 5
-    sys_unimplemented,        // 6
-    sys_unimplemented,        // 7
-    sys_unimplemented,        // 8
-    sys_unimplemented,        // 9
-    sys_unimplemented,        // 10
-    sys_unimplemented,        // 11
-    sys_unimplemented,        // 12
-    sys_unimplemented,        // 13
-    sys_unimplemented,        // 14
-    sys_unimplemented,        // 15
-    sys_unimplemented,        // 16
-    sys_unimplemented,        // 17
-    sys_unimplemented,        // 18
-    sys_unimplemented,        // 19
-    sys_unimplemented,        // 20
-    sys_unimplemented,        // 21
-    sys_unimplemented,        // 22
-    sys_unimplemented,        // 23
-    sys_unimplemented,        // 24
-    sys_unimplemented,        // 25
-    do_getinfo::do_getinfo,   // 26 SYS_GETINFO
-    sys_unimplemented,        // 27
-    sys_unimplemented,        // 28
-    sys_unimplemented,        // 29
-    sys_unimplemented,        // 30
-    sys_unimplemented,        // 31
-    sys_unimplemented,        // 32
-    sys_unimplemented,        // 33
-    do_setgrant::do_setgrant, // 34 SYS_SETGRANT
-    sys_unimplemented,        // 35
-    sys_unimplemented,        // 36
-    sys_unimplemented,        // 37
-    sys_unimplemented,        // 38
-    sys_unimplemented,        // 39
-    sys_unimplemented,        // 40
-    sys_unimplemented,        // 41
-    sys_unimplemented,        // 42
-    sys_unimplemented,        // 43
-    do_diagctl::do_diagctl,   // 44 SYS_DIAGCTL
-    sys_unimplemented,        // 45
-    sys_unimplemented,        // 46
-    sys_unimplemented,        // 47
-    sys_unimplemented,        // 48
-    sys_unimplemented,        // 49
-    sys_unimplemented,        // 50
-    sys_unimplemented,        // 51
-    sys_unimplemented,        // 52
-    do_exit::do_exit,         // 53 SYS_EXIT
-    sys_unimplemented,        // 54
-    do_statectl::do_statectl, // 55 SYS_STATECTL
-    sys_unimplemented,        // 56
-    sys_unimplemented,        // 57
+    do_fork::do_fork,            // 0 SYS_FORK
+    do_exec::do_exec,            // 1 SYS_EXEC
+    do_clear::do_clear,          // 2 SYS_CLEAR
+    sys_unimplemented,           // 3
+    sys_unimplemented,           // 4 SYS_PRIVCTL
+    sys_unimplemented,           // 5
+    do_kill::do_kill,            // 6 SYS_KILL
+    do_getksig::do_getksig,      // 7 SYS_GETKSIG
+    do_endksig::do_endksig,      // 8 SYS_ENDKSIG
+    do_sigsend::do_sigsend,      // 9 SYS_SIGSEND
+    do_sigreturn::do_sigreturn,  // 10 SYS_SIGRETURN
+    sys_unimplemented,           // 11
+    sys_unimplemented,           // 12
+    sys_unimplemented,           // 13
+    sys_unimplemented,           // 14
+    sys_unimplemented,           // 15
+    sys_unimplemented,           // 16
+    sys_unimplemented,           // 17
+    sys_unimplemented,           // 18
+    sys_unimplemented,           // 19
+    sys_unimplemented,           // 20
+    sys_unimplemented,           // 21
+    sys_unimplemented,           // 22
+    sys_unimplemented,           // 23
+    do_setalarm::do_setalarm,    // 24 SYS_SETALARM
+    do_times::do_times,          // 25 SYS_TIMES
+    do_getinfo::do_getinfo,      // 26 SYS_GETINFO
+    sys_unimplemented,           // 27
+    sys_unimplemented,           // 28
+    sys_unimplemented,           // 29
+    sys_unimplemented,           // 30
+    sys_unimplemented,           // 31
+    sys_unimplemented,           // 32
+    sys_unimplemented,           // 33
+    do_setgrant::do_setgrant,    // 34 SYS_SETGRANT
+    sys_unimplemented,           // 35
+    sys_unimplemented,           // 36
+    sys_unimplemented,           // 37
+    sys_unimplemented,           // 38
+    do_stime::do_stime,          // 39 SYS_STIME
+    do_settime::do_settime,      // 40 SYS_SETTIME
+    sys_unimplemented,           // 41
+    sys_unimplemented,           // 42
+    sys_unimplemented,           // 43
+    do_diagctl::do_diagctl,      // 44 SYS_DIAGCTL
+    sys_unimplemented,           // 45
+    sys_unimplemented,           // 46
+    sys_unimplemented,           // 47
+    sys_unimplemented,           // 48
+    sys_unimplemented,           // 49
+    do_mcontext::do_getmcontext, // 50 SYS_GETMCONTEXT
+    do_mcontext::do_setmcontext, // 51 SYS_SETMCONTEXT
+    sys_unimplemented,           // 52
+    do_exit::do_exit,            // 53 SYS_EXIT
+    sys_unimplemented,           // 54
+    do_statectl::do_statectl,    // 55 SYS_STATECTL
+    sys_unimplemented,           // 56
+    sys_unimplemented,           // 57
 ];
 
 fn sys_unimplemented(
@@ -132,3 +313,373 @@ fn sys_unimplemented(
     let call_nr = message.m_type;
     unimplemented!("Kernel call nr: {:#x} from {}", call_nr, caller_endpoint);
 }
+
+#[cfg(test)]
+mod tests {
+    use nix::sys::{
+        signal::Signal::{SIGSEGV, SIGTRAP},
+        wait::WaitStatus,
+    };
+
+    use super::*;
+    use crate::test_utils::{run_processes, run_processes_with, test_bin};
+    use crate::utils::{endpoint, MinixProcess};
+
+    #[test]
+    fn sys_fork_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // use slot 43 once, so that the child gets the next generation
+        let process = MinixProcess::spawn(&test_bin("syscall")).unwrap();
+        let _ = process_table.insert(process, 43);
+        clear_endpoint(43, &mut process_table).unwrap();
+        assert!(run_processes(&mut process_table).is_empty());
+        let child_endpoint = process_table.next_endpoint(43).unwrap();
+        assert_eq!(child_endpoint, endpoint::make_endpoint(1, 43));
+
+        // the user process asks PM to fork it; PM forks it with SYS_FORK,
+        // and replies to the parent with the child's endpoint, and to the child with 0
+        let mut pm = MinixProcess::spawn(&test_bin("fork_pm")).unwrap();
+        pm.privileges = Priv::system(static_priv_id(endpoint::PM_PROC_NR));
+        let _ = process_table.insert(pm, endpoint::PM_PROC_NR);
+        let _ = process_table.insert(MinixProcess::spawn(&test_bin("fork_user")).unwrap(), 42);
+
+        // PM and the parent exit with 0 on success, the child with 100
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(0, 0), (42, 0), (child_endpoint, 100)]);
+    }
+
+    #[test]
+    fn sys_exec_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // the user process asks PM for an exec, and PM sets its new registers
+        // and name with SYS_EXEC. The user process resumes without a reply
+        let mut pm = MinixProcess::spawn(&test_bin("exec_pm")).unwrap();
+        pm.privileges = Priv::system(static_priv_id(endpoint::PM_PROC_NR));
+        let _ = process_table.insert(pm, endpoint::PM_PROC_NR);
+        let user = MinixProcess::spawn(&test_bin("exec_user")).unwrap();
+        let user_pid = user.pid();
+        let _ = process_table.insert(user, 42);
+
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            if let WaitStatus::Exited(pid, _) = *status {
+                if pid == user_pid {
+                    assert_eq!(process_table[42].name, "exec_new");
+                    assert!(!process_table[42].reply_pending);
+                }
+            }
+            true
+        });
+
+        // both processes exit with 0 on success
+        assert_eq!(statuses, vec![(0, 0), (42, 0)]);
+    }
+
+    #[test]
+    fn sys_exit_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 41 exits with SYS_EXIT while 42 waits for its reply. 41 is torn
+        // down and killed, and the SENDREC of 42 fails with EDEADSRCDST
+        for proc_nr in [41, 42] {
+            let path = test_bin(&format!("exit_{}", proc_nr));
+            let mut process = MinixProcess::spawn(&path).unwrap();
+            process.privileges = Priv::system(static_priv_id(proc_nr));
+            let _ = process_table.insert(process, proc_nr);
+        }
+
+        // 42 exits with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(42, 0)]);
+    }
+
+    #[test]
+    fn sys_clear_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // PM clears the user process waiting for its reply with SYS_CLEAR,
+        // which kills it. Its endpoint is no longer valid, for SYS_CLEAR or ipc
+        let mut pm = MinixProcess::spawn(&test_bin("clear_pm")).unwrap();
+        pm.privileges = Priv::system(static_priv_id(endpoint::PM_PROC_NR));
+        let _ = process_table.insert(pm, endpoint::PM_PROC_NR);
+        let _ = process_table.insert(MinixProcess::spawn(&test_bin("clear_user")).unwrap(), 42);
+
+        // PM exits with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(endpoint::PM_PROC_NR, 0)]);
+
+        // the next process in the slot gets a new endpoint
+        assert_eq!(
+            process_table.next_endpoint(42).unwrap(),
+            endpoint::make_endpoint(1, 42)
+        );
+    }
+
+    #[test]
+    fn kernel_signal_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // the signal manager signals the target with SYS_KILL, and handles
+        // the signal with SYS_GETKSIG and SYS_ENDKSIG. The target shouldn't
+        // run in the meantime, even though it's notified
+        let mut manager = MinixProcess::spawn(&test_bin("ksig_mgr")).unwrap();
+        manager.privileges = Priv::system(static_priv_id(41));
+        let _ = process_table.insert(manager, 41);
+        let mut target = MinixProcess::spawn(&test_bin("ksig_target")).unwrap();
+        target.privileges = Priv::system(static_priv_id(42));
+        target.privileges.s_sig_mgr = 41;
+        let target_pid = target.pid();
+        let _ = process_table.insert(target, 42);
+
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            if let WaitStatus::Exited(pid, _) = *status {
+                if pid == target_pid {
+                    // the target's signals were handled
+                    assert_eq!(process_table[42].rts_flags, 0);
+                }
+            }
+            true
+        });
+
+        // the signal manager exits with 0 on success, and
+        // the target with the source of the notification
+        assert_eq!(statuses, vec![(41, 0), (42, 41)]);
+    }
+
+    #[test]
+    fn kernel_signal_twice_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // the signal manager signals the running target twice with SYS_KILL,
+        // before handling both signals. The second SYS_KILL finds the target
+        // already held stopped, and mustn't wait for it to stop again
+        let mut manager = MinixProcess::spawn(&test_bin("ksig_twice_mgr")).unwrap();
+        manager.privileges = Priv::system(static_priv_id(41));
+        let manager_pid = manager.pid();
+        let _ = process_table.insert(manager, 41);
+        let mut target = MinixProcess::spawn(&test_bin("spin")).unwrap();
+        target.privileges.s_sig_mgr = 41;
+        let _ = process_table.insert(target, 42);
+
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            if let WaitStatus::Exited(pid, _) = *status {
+                if pid == manager_pid {
+                    // the target runs again, with no signals pending.
+                    // It never exits, so it's cleared
+                    assert_eq!(process_table[42].rts_flags, 0);
+                    assert!(!process_table[42].is_signal_stopped());
+                    clear_endpoint(42, process_table).unwrap();
+                }
+            }
+            true
+        });
+
+        // the signal manager exits with 0 on success
+        assert_eq!(statuses, vec![(41, 0)]);
+    }
+
+    #[test]
+    fn linux_signal_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // the target divides by zero, and the SIGFPE it gets from Linux
+        // is passed to its signal manager. It's not resumed, since
+        // the signal manager never finishes handling the signal
+        let mut manager = MinixProcess::spawn(&test_bin("sigfpe_mgr")).unwrap();
+        manager.privileges = Priv::system(static_priv_id(41));
+        let manager_pid = manager.pid();
+        let _ = process_table.insert(manager, 41);
+        let mut target = MinixProcess::spawn(&test_bin("sigfpe")).unwrap();
+        target.privileges.s_sig_mgr = 41;
+        let _ = process_table.insert(target, 42);
+
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            if let WaitStatus::Exited(pid, _) = *status {
+                if pid == manager_pid {
+                    // the target is still held stopped, with the signal pending
+                    let rts_flags = process_table[42].rts_flags;
+                    assert!(rts_flags & rts_flags::RTS_SIG_PENDING != 0);
+                    clear_endpoint(42, process_table).unwrap();
+                }
+            }
+            true
+        });
+
+        // the signal manager exits with 0 on success
+        assert_eq!(statuses, vec![(41, 0)]);
+    }
+
+    #[test]
+    fn sigframe_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // the process makes itself run a signal handler with SYS_SIGSEND,
+        // and returns from it with SYS_SIGRETURN, which restores its registers
+        let mut process = MinixProcess::spawn(&test_bin("sigframe")).unwrap();
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+
+        // the process exits with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(42, 0)]);
+    }
+
+    #[test]
+    fn sigsend_blocked_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 42 waits for the reply to its SENDREC, when 41 makes it run a
+        // signal handler with SYS_SIGSEND. The handler runs right away,
+        // and the interrupted SENDREC returns EINTR after SYS_SIGRETURN
+        for proc_nr in [41, 42] {
+            let path = test_bin(&format!("sigsend_blocked_{}", proc_nr));
+            let mut process = MinixProcess::spawn(&path).unwrap();
+            process.privileges = Priv::system(static_priv_id(proc_nr));
+            let _ = process_table.insert(process, proc_nr);
+        }
+
+        // both processes exit with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(41, 0), (42, 0)]);
+    }
+
+    #[test]
+    fn mcontext_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // the process saves its machine context with SYS_GETMCONTEXT, changes
+        // its registers, and restores the context with SYS_SETMCONTEXT
+        let mut process = MinixProcess::spawn(&test_bin("mcontext")).unwrap();
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+
+        // the process exits with 0 on success
+        let statuses = run_processes(&mut process_table);
+        assert_eq!(statuses, vec![(42, 0)]);
+    }
+
+    #[test]
+    fn sys_times_test() {
+        use std::convert::TryInto;
+
+        let mut process_table = MinixProcessTable::new();
+
+        // the process uses some CPU time, and gets its times with SYS_TIMES
+        let mut process = MinixProcess::spawn(&test_bin("times")).unwrap();
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+
+        let mut addr = 0;
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            match *status {
+                WaitStatus::Stopped(_, SIGSEGV) => {
+                    addr = process_table[42].get_regs().unwrap().rax;
+                    return true;
+                }
+                // the process stops right after the kernel call
+                WaitStatus::Stopped(_, SIGTRAP) => {}
+                _ => return true,
+            }
+
+            let reply = process_table[42].read_buf_u8(addr, 64).unwrap();
+            let field =
+                |offset: usize| u32::from_le_bytes(reply[offset..offset + 4].try_into().unwrap());
+            let (real_ticks, boot_ticks) = (field(8) as u64, field(12) as u64);
+            let (user_time, system_time) = (field(16) as u64, field(20) as u64);
+            let boot_time = i64::from_le_bytes(reply[24..32].try_into().unwrap());
+
+            // the CPU times are those Linux accounted to the process so far,
+            // and fit in the time since boot
+            let (linux_user, linux_system) = process_table[42].cpu_times().unwrap();
+            assert!(user_time > 0 && user_time <= linux_user);
+            assert!(system_time <= linux_system);
+            let uptime = process_table.clock.uptime();
+            assert!(boot_ticks > 0 && boot_ticks <= uptime);
+            assert!(user_time + system_time <= uptime);
+
+            // the real time is counted from the boot time
+            let (boottime, realtime) = process_table.clock.wall_time();
+            assert_eq!(boot_time, boottime as i64);
+            assert!(real_ticks <= realtime);
+
+            process_table[42].cont().unwrap();
+            false
+        });
+
+        // the process exits with the result of the kernel call
+        assert_eq!(statuses, vec![(42, 0)]);
+    }
+
+    #[test]
+    fn sys_times_gone_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // 43 is killed and reaped before it's cleared, so Linux
+        // doesn't account any times to it anymore
+        let process = MinixProcess::spawn(&test_bin("spin")).unwrap();
+        nix::sys::signal::kill(process.pid(), nix::sys::signal::Signal::SIGKILL).unwrap();
+        while let WaitStatus::Stopped(_, _) = nix::sys::wait::waitpid(process.pid(), None).unwrap()
+        {
+        }
+        let _ = process_table.insert(process, 43);
+
+        // 41 gets the times of 43 with SYS_TIMES, which are 0
+        let mut process = MinixProcess::spawn(&test_bin("times_gone_41")).unwrap();
+        process.privileges = Priv::system(static_priv_id(41));
+        let _ = process_table.insert(process, 41);
+
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            if let WaitStatus::Exited(_, _) = status {
+                process_table.remove(43);
+            }
+            true
+        });
+
+        // the process exits with 0 on success
+        assert_eq!(statuses, vec![(41, 0)]);
+    }
+
+    #[test]
+    fn settime_test() {
+        let mut process_table = MinixProcessTable::new();
+
+        // the new time is 100 seconds past the boot time, so it
+        // fits the real time of the processes
+        let boottime = 1_999_999_900;
+        process_table.clock.set_boottime(boottime);
+
+        // the process sets the time with SYS_SETTIME, tries invalid
+        // nanoseconds and another clock, adjusts the time back by
+        // 1.5 seconds, and then sets the boot time with SYS_STIME
+        let mut process = MinixProcess::spawn(&test_bin("settime")).unwrap();
+        process.privileges = Priv::system(static_priv_id(42));
+        let _ = process_table.insert(process, 42);
+
+        let hz = crate::HZ as u64;
+        let mut calls = 0;
+        let statuses = run_processes_with(&mut process_table, |status, process_table| {
+            if let WaitStatus::Stopped(_, SIGSEGV) = status {
+                calls += 1;
+                if calls == 2 {
+                    // after SYS_SETTIME, the boot time is kept,
+                    // and the real time counts the ticks since then
+                    let expected = (2_000_000_000 - boottime) * hz + hz / 2;
+                    let (new_boottime, realtime) = process_table.clock.wall_time();
+                    assert_eq!(new_boottime, boottime);
+                    assert!(realtime >= expected && realtime < expected + hz);
+                }
+            }
+            true
+        });
+
+        // the process exits with 0 on success
+        assert_eq!(statuses, vec![(42, 0)]);
+
+        // the negative nanoseconds count towards the adjustment,
+        // which is applied gradually
+        assert!(process_table.clock.adjtime_delta() < -(hz as i64));
+
+        assert_eq!(process_table.clock.boottime(), 1_000_000_000);
+    }
+}
@@ -17,15 +17,15 @@ impl BootImage {
         Self {
             proc_nr,
             proc_name: proc_name.into(),
-            endpoint: proc_nr, // TODO: fix this, this is cheating
+            endpoint: make_endpoint(0, proc_nr),
             start_addr: 0,
             len: 0,
         }
     }
 
-    // TODO: change this into a static constant
-    pub fn image() -> [u8; NR_BOOT_PROCS * size_of::<BootImage>()] {
-        let image: [BootImage; NR_BOOT_PROCS] = [
+    /// the boot image table, in the order Minix keeps it
+    pub fn table() -> [BootImage; NR_BOOT_PROCS] {
+        [
             Self::new(ASYNCM, b"asyncm"),
             Self::new(IDLE, b"idle"),
             Self::new(CLOCK, b"clock"),
@@ -43,9 +43,12 @@ impl BootImage {
             Self::new(PFS_PROC_NR, b"pfs"),
             Self::new(MFS_PROC_NR, b"mfs"),
             Self::new(INIT_PROC_NR, b"init"),
-        ];
+        ]
+    }
 
-        unsafe { std::mem::transmute(image) }
+    // TODO: change this into a static constant
+    pub fn image() -> [u8; NR_BOOT_PROCS * size_of::<BootImage>()] {
+        unsafe { std::mem::transmute(Self::table()) }
     }
 }
 
@@ -1,12 +1,19 @@
 use super::*;
 
+/// the kernel release and version, reported in `KInfo`
+const OS_RELEASE: &[u8; 6] = b"3\0\0\0\0\0";
+const OS_VERSION: &[u8; 6] = b"4.0\0\0\0";
+
+/// the top of the user stack, as in Minix on i386
+pub const USER_SP_TOP: VirBytes = 0xf0000000;
+
 #[repr(C)]
 #[allow(dead_code)]
 pub struct KInfo {
     // Straight multiboot-provided info
-    pub mbi: u32,
-    pub module_list: [u32; MULTIBOOT_MAX_MODS],
-    pub memmap: [u32; MAXMEMMAP],
+    pub mbi: MultibootInfo,
+    pub module_list: [MultibootModule; MULTIBOOT_MAX_MODS],
+    pub memmap: [MultibootMemoryMap; MAXMEMMAP],
     pub mem_high_phys: u32,
     pub mmap_size: i32,
 
@@ -37,3 +44,64 @@ pub struct KInfo {
     pub kernel_allocated_bytes: i32,         /* used by kernel */
     pub kernel_allocated_bytes_dynamic: i32, /* used by kernel (runtime) */
 }
+
+impl KInfo {
+    /// the kernel information, with the messages buffer at `kmessages`.
+    /// There's no multiboot loader, so the multiboot fields stay empty
+    pub fn new(nr_procs: usize, kmessages: u32) -> Self {
+        let mut kinfo: Self = unsafe { std::mem::transmute([0u8; std::mem::size_of::<Self>()]) };
+
+        kinfo.kmessages = kmessages;
+        kinfo.user_sp = USER_SP_TOP;
+        kinfo.user_end = USER_SP_TOP;
+        kinfo.boot_procs = BootImage::table();
+        kinfo.nr_procs = nr_procs as i32;
+        kinfo.nr_tasks = NR_TASKS;
+        kinfo.release = *OS_RELEASE;
+        kinfo.version = *OS_VERSION;
+
+        kinfo
+    }
+}
+
+// The multiboot types are defined in `include/minix/multiboot.h`
+#[repr(C)]
+pub struct MultibootInfo {
+    pub flags: u32,
+    pub mem_lower_unused: u32, // minix uses memmap instead
+    pub mem_upper_unused: u32,
+    pub boot_device: u32,
+    pub cmdline: u32,
+    pub mods_count: u32,
+    pub mods_addr: u32,
+    pub syms: [u32; 4], // a.out or elf symbol table
+    pub mmap_length: u32,
+    pub mmap_addr: u32,
+    pub drives_length: u32,
+    pub drives_addr: u32,
+    pub config_table: u32,
+    pub boot_loader_name: u32,
+    pub apm_table: u32,
+    pub vbe_control_info: u32,
+    pub vbe_mode_info: u32,
+    pub vbe_mode: u16,
+    pub vbe_interface_seg: u16,
+    pub vbe_interface_off: u16,
+    pub vbe_interface_len: u16,
+}
+
+#[repr(C)]
+pub struct MultibootModule {
+    pub mod_start: u32,
+    pub mod_end: u32,
+    pub cmdline: u32,
+    pub pad: u32,
+}
+
+#[repr(C, packed)]
+pub struct MultibootMemoryMap {
+    pub size: u32,
+    pub mm_base_addr: u64,
+    pub mm_length: u64,
+    pub r#type: u32,
+}
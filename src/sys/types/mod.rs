@@ -1,8 +1,10 @@
 use super::NR_SYS_CALLS;
+use crate::utils::endpoint::{INIT_PROC_NR, NONE, NR_TASKS, PM_PROC_NR, RS_PROC_NR};
 use crate::utils::Endpoint;
 
 mod boot_image;
 mod kinfo;
+pub mod signal;
 
 pub use boot_image::*;
 pub use kinfo::*;
@@ -16,6 +18,15 @@ pub const MULTIBOOT_MAX_MODS: usize = 20;
 pub const MAXMEMMAP: usize = 40;
 pub const MULTIBOOT_PARAM_BUF_SIZE: usize = 1024;
 
+// static privilege ids, defined in kernel/priv.h
+pub const fn static_priv_id(proc_nr: Endpoint) -> i16 {
+    (NR_TASKS + proc_nr) as i16
+}
+/// unprivileged user processes all share the privilege id of the root user process
+pub const USER_PRIV_ID: i16 = static_priv_id(INIT_PROC_NR);
+/// user processes may only use SENDREC (USR_T in Minix)
+const USER_TRAP_MASK: i16 = 1 << 3;
+
 pub const IPCF_MAX_ELEMENTS: usize = NR_SYS_PROCS * 2;
 const NR_IO_RANGE: usize = 64;
 const NR_MEM_RANGE: usize = 20;
@@ -24,6 +35,7 @@ const NR_IRQ: usize = 16;
 type VirBytes = u32;
 
 #[repr(C)]
+#[derive(Clone, Default, Debug)]
 pub struct SigSet(u32, u32, u32, u32);
 
 // r# escapes reserved names
@@ -34,6 +46,7 @@ mod r#priv {
     use crate::utils::{as_buf_u8, Endpoint};
 
     #[repr(C)]
+    #[derive(Clone)]
     pub struct Priv {
         pub s_proc_nr: i32,    /* number of associated process */
         pub s_id: i16,         /* index of this system structure */
@@ -87,6 +100,52 @@ mod r#priv {
     }
 
     impl Priv {
+        /// the privileges of an unprivileged user process.
+        /// Like in Minix, it may only use SENDREC, to send requests
+        /// to system processes, and makes no kernel calls.
+        /// The signals of user processes are managed by PM
+        pub fn user() -> Self {
+            let mut privileges = Self {
+                s_id: USER_PRIV_ID,
+                s_trap_mask: USER_TRAP_MASK,
+                s_sig_mgr: PM_PROC_NR,
+                s_bak_sig_mgr: NONE,
+                ..Self::default()
+            };
+            privileges.s_ipc_to.fill();
+            privileges.s_ipc_to.unset(USER_PRIV_ID as usize);
+            privileges
+        }
+
+        /// the privileges of a system process with the given privilege id.
+        /// Since privileges of system processes aren't set up
+        /// through SYS_PRIVCTL yet, these don't restrict
+        /// the ipc traps, their destinations, or the kernel calls
+        pub fn system(s_id: i16) -> Self {
+            let mut privileges = Self {
+                s_id,
+                s_trap_mask: !0,
+                s_sig_mgr: RS_PROC_NR,
+                s_bak_sig_mgr: NONE,
+                ..Self::default()
+            };
+            privileges.s_ipc_to.fill();
+            for call_nr in 0..NR_SYS_CALLS {
+                set_bit(&mut privileges.s_k_call_mask, call_nr);
+            }
+            privileges
+        }
+
+        /// checks if the kernel call is allowed by the kernel call mask
+        pub fn may_call(&self, call_nr: usize) -> bool {
+            get_bit(&self.s_k_call_mask, call_nr)
+        }
+
+        /// checks if the ipc trap is allowed by the trap mask
+        pub fn may_trap(&self, call_nr: u64) -> bool {
+            call_nr < 16 && self.s_trap_mask & (1 << call_nr) != 0
+        }
+
         pub fn as_buf(&self) -> [u8; size_of::<Priv>()] {
             as_buf_u8(self)
         }
@@ -94,17 +153,60 @@ mod r#priv {
 }
 
 #[repr(C)]
+#[derive(Clone, Default)]
 pub struct SysMap {
     chunk: [BitChunk; bitmap_chunks(NR_SYS_PROCS)],
 }
 
+impl SysMap {
+    /// checks if the bit for the privilege id is set
+    pub fn get(&self, id: usize) -> bool {
+        get_bit(&self.chunk, id)
+    }
+
+    pub fn set(&mut self, id: usize) {
+        set_bit(&mut self.chunk, id);
+    }
+
+    pub fn unset(&mut self, id: usize) {
+        unset_bit(&mut self.chunk, id);
+    }
+
+    /// sets the bits for all privilege ids
+    pub fn fill(&mut self) {
+        for id in 0..NR_SYS_PROCS {
+            self.set(id);
+        }
+    }
+
+    /// returns an iterator over the privilege ids with
+    /// their bit set, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..NR_SYS_PROCS).filter(move |&id| self.get(id))
+    }
+}
+
 const BITCHUNK_BITS: usize = std::mem::size_of::<BitChunk>() * 8;
 const fn bitmap_chunks(nr_bits: usize) -> usize {
-    (nr_bits + BITCHUNK_BITS - 1) / BITCHUNK_BITS
+    nr_bits.div_ceil(BITCHUNK_BITS)
 }
 const SYS_CALL_MASK_SIZE: usize = bitmap_chunks(NR_SYS_CALLS);
 
+// bitmap operations, like the GET_BIT/SET_BIT/UNSET_BIT macros in Minix
+fn get_bit(map: &[BitChunk], bit: usize) -> bool {
+    map[bit / BITCHUNK_BITS].0 & (1 << (bit % BITCHUNK_BITS)) != 0
+}
+
+fn set_bit(map: &mut [BitChunk], bit: usize) {
+    map[bit / BITCHUNK_BITS].0 |= 1 << (bit % BITCHUNK_BITS);
+}
+
+fn unset_bit(map: &mut [BitChunk], bit: usize) {
+    map[bit / BITCHUNK_BITS].0 &= !(1 << (bit % BITCHUNK_BITS));
+}
+
 #[repr(C)]
+#[derive(Clone, Default)]
 pub struct BitChunk(u32);
 
 #[repr(C)]
@@ -124,6 +226,7 @@ struct IpcFilterElement {
 }
 
 #[repr(C)]
+#[derive(Clone)]
 pub struct MinixTimer {
     tmr_next: u32,     // next in a timer chain, type is MinixTimer*
     tmr_exp_time: u32, // expiration time (type is unsigned int or long)
@@ -131,14 +234,98 @@ pub struct MinixTimer {
     tmr_arg: i32,      // integer argument
 }
 
+/// the expiration time of a timer that's not set
+pub const TMR_NEVER: u32 = u32::MAX;
+
+/// the value of `tmr_func` for the synchronous alarms. Minix keeps a function
+/// pointer there, but the alarm notification is the only timer function we have
+const TMR_FUNC_ALARM: u32 = 1;
+
+impl MinixTimer {
+    pub fn is_set(&self) -> bool {
+        self.tmr_func != 0
+    }
+
+    /// sets the timer to expire at `exp_time` (in clock ticks), and
+    /// notify the process `arg` with a synchronous alarm
+    pub fn set(&mut self, exp_time: u32, arg: Endpoint) {
+        self.tmr_exp_time = exp_time;
+        self.tmr_func = TMR_FUNC_ALARM;
+        self.tmr_arg = arg;
+    }
+
+    pub fn reset(&mut self) {
+        self.tmr_exp_time = TMR_NEVER;
+        self.tmr_func = 0;
+    }
+
+    pub fn exp_time(&self) -> u32 {
+        self.tmr_exp_time
+    }
+
+    pub fn arg(&self) -> Endpoint {
+        self.tmr_arg
+    }
+}
+
+/// checks if the time `a` comes before the time `b`, accounting
+/// for the wraparound of the clock (`tmr_is_first` in Minix)
+pub fn tmr_is_first(a: u32, b: u32) -> bool {
+    a.wrapping_sub(b) > i32::MAX as u32
+}
+
 #[repr(C)]
+#[derive(Clone)]
 pub struct IoRange {
     ior_base: u32,
     ior_limit: u32,
 }
 
 #[repr(C)]
+#[derive(Clone)]
 pub struct MinixMemRange {
     mr_base: u32,
     mr_limit: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sys_map_test() {
+        let mut map = Priv::default().s_ipc_to;
+        assert_eq!(map.iter().count(), 0);
+
+        map.set(3);
+        map.set(40);
+        map.set(NR_SYS_PROCS - 1);
+        assert!(map.get(40) && !map.get(41));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![3, 40, NR_SYS_PROCS - 1]
+        );
+
+        map.unset(40);
+        assert_eq!(map.iter().collect::<Vec<_>>(), vec![3, NR_SYS_PROCS - 1]);
+
+        map.fill();
+        assert_eq!(map.iter().count(), NR_SYS_PROCS);
+    }
+
+    #[test]
+    fn user_priv_test() {
+        // user processes may only SENDREC to system processes,
+        // and make no kernel calls
+        let user = Priv::user();
+        assert!(user.may_trap(3));
+        assert!(!user.may_trap(1) && !user.may_trap(2) && !user.may_trap(4));
+        assert!((0..NR_SYS_CALLS).all(|call_nr| !user.may_call(call_nr)));
+        assert!(!user.s_ipc_to.get(USER_PRIV_ID as usize));
+        assert!(user.s_ipc_to.get(static_priv_id(PM_PROC_NR) as usize));
+
+        let system = Priv::system(static_priv_id(42));
+        assert!((0..16).all(|call_nr| system.may_trap(call_nr)));
+        assert!((0..NR_SYS_CALLS).all(|call_nr| system.may_call(call_nr)));
+    }
+}
@@ -0,0 +1,273 @@
+// Minix signal numbers, defined in `include/signal.h` and `sys/sys/signal.h`.
+// These differ from the Linux ones
+use nix::sys::signal::Signal;
+
+use super::SigSet;
+use crate::utils::{Endpoint, MessagePayload, Payload};
+
+pub const SIGHUP: i32 = 1; // hangup
+pub const SIGINT: i32 = 2; // interrupt
+pub const SIGQUIT: i32 = 3; // quit
+pub const SIGILL: i32 = 4; // illegal instruction
+pub const SIGTRAP: i32 = 5; // trace trap
+pub const SIGABRT: i32 = 6; // abort()
+pub const SIGEMT: i32 = 7; // EMT instruction
+pub const SIGFPE: i32 = 8; // floating point exception
+pub const SIGKILL: i32 = 9; // kill (cannot be caught or ignored)
+pub const SIGBUS: i32 = 10; // bus error
+pub const SIGSEGV: i32 = 11; // segmentation violation
+pub const SIGSYS: i32 = 12; // bad argument to system call
+pub const SIGPIPE: i32 = 13; // write on a pipe with no one to read it
+pub const SIGALRM: i32 = 14; // alarm clock
+pub const SIGTERM: i32 = 15; // software termination signal from kill
+pub const SIGURG: i32 = 16; // urgent condition on IO channel
+pub const SIGSTOP: i32 = 17; // sendable stop signal not from tty
+pub const SIGTSTP: i32 = 18; // stop signal from tty
+pub const SIGCONT: i32 = 19; // continue a stopped process
+pub const SIGCHLD: i32 = 20; // to parent on child stop or exit
+pub const SIGTTIN: i32 = 21; // to readers pgrp upon background tty read
+pub const SIGTTOU: i32 = 22; // like TTIN for output if (tp->t_local&LTOSTOP)
+pub const SIGIO: i32 = 23; // input/output possible signal
+pub const SIGXCPU: i32 = 24; // exceeded CPU time limit
+pub const SIGXFSZ: i32 = 25; // exceeded file size limit
+pub const SIGVTALRM: i32 = 26; // virtual time alarm
+pub const SIGPROF: i32 = 27; // profiling time alarm
+pub const SIGWINCH: i32 = 28; // window size changes
+pub const SIGINFO: i32 = 29; // information request
+pub const SIGUSR1: i32 = 30; // user defined signal 1
+pub const SIGUSR2: i32 = 31; // user defined signal 2
+pub const SIGPWR: i32 = 32; // power fail/restart
+
+// signals delivered by the kernel to the system processes
+pub const SIGKMEM: i32 = 71; // kernel memory request pending
+pub const SIGKMESS: i32 = 72; // new kernel message
+pub const SIGKSIGSM: i32 = 73; // kernel signal pending for signal manager
+pub const SIGKSIG: i32 = 74; // kernel signal pending
+
+pub const NSIG: i32 = 75;
+
+/// signals which terminate a system process,
+/// unless it has a backup signal manager
+pub fn sigs_is_lethal(sig: i32) -> bool {
+    matches!(sig, SIGILL | SIGBUS | SIGFPE | SIGSEGV | SIGEMT | SIGABRT)
+}
+
+/// the Minix signal corresponding to a Linux signal received by a process,
+/// or `None` if it has no Minix counterpart. SIGCHLD isn't translated,
+/// since PM signals the parent itself when a Minix process exits
+pub fn from_linux_signal(sig: Signal) -> Option<i32> {
+    let sig = match sig {
+        Signal::SIGHUP => SIGHUP,
+        Signal::SIGINT => SIGINT,
+        Signal::SIGQUIT => SIGQUIT,
+        Signal::SIGILL => SIGILL,
+        Signal::SIGTRAP => SIGTRAP,
+        Signal::SIGABRT => SIGABRT,
+        Signal::SIGBUS => SIGBUS,
+        Signal::SIGFPE => SIGFPE,
+        Signal::SIGKILL => SIGKILL,
+        Signal::SIGUSR1 => SIGUSR1,
+        Signal::SIGSEGV => SIGSEGV,
+        Signal::SIGUSR2 => SIGUSR2,
+        Signal::SIGPIPE => SIGPIPE,
+        Signal::SIGALRM => SIGALRM,
+        Signal::SIGTERM => SIGTERM,
+        Signal::SIGCONT => SIGCONT,
+        Signal::SIGSTOP => SIGSTOP,
+        Signal::SIGTSTP => SIGTSTP,
+        Signal::SIGTTIN => SIGTTIN,
+        Signal::SIGTTOU => SIGTTOU,
+        Signal::SIGURG => SIGURG,
+        Signal::SIGXCPU => SIGXCPU,
+        Signal::SIGXFSZ => SIGXFSZ,
+        Signal::SIGVTALRM => SIGVTALRM,
+        Signal::SIGPROF => SIGPROF,
+        Signal::SIGWINCH => SIGWINCH,
+        Signal::SIGIO => SIGIO,
+        Signal::SIGPWR => SIGPWR,
+        Signal::SIGSYS => SIGSYS,
+        _ => return None,
+    };
+    Some(sig)
+}
+
+impl SigSet {
+    /// adds the signal to the set. Invalid signal numbers are ignored
+    pub fn add(&mut self, sig: i32) {
+        if let Some((word, mask)) = Self::position(sig) {
+            *self.word_mut(word) |= mask;
+        }
+    }
+
+    /// removes the signal from the set. Invalid signal numbers are ignored
+    pub fn del(&mut self, sig: i32) {
+        if let Some((word, mask)) = Self::position(sig) {
+            *self.word_mut(word) &= !mask;
+        }
+    }
+
+    pub fn is_member(&self, sig: i32) -> bool {
+        match Self::position(sig) {
+            Some((word, mask)) => self.words()[word] & mask != 0,
+            None => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words().iter().all(|&word| word == 0)
+    }
+
+    /// the word and the bit of the signal, like `__sigword` and `__sigmask`,
+    /// or `None` if the signal number doesn't fit in the set
+    fn position(sig: i32) -> Option<(usize, u32)> {
+        if sig <= 0 || sig as usize > 32 * 4 {
+            return None;
+        }
+        let bit = (sig - 1) as usize;
+        Some((bit / 32, 1 << (bit % 32)))
+    }
+
+    fn words(&self) -> [u32; 4] {
+        [self.0, self.1, self.2, self.3]
+    }
+
+    fn word_mut(&mut self, word: usize) -> &mut u32 {
+        match word {
+            0 => &mut self.0,
+            1 => &mut self.1,
+            2 => &mut self.2,
+            _ => &mut self.3,
+        }
+    }
+}
+
+/// the message used by the signal related kernel calls
+#[repr(C)]
+#[derive(Debug)]
+pub struct MessageSigCalls {
+    pub map: SigSet,     // used to pass signal bit map
+    pub endpt: Endpoint, // process number for inform
+    pub sig: i32,        // signal number to send
+    pub sigctx: u32,     // pointer to signal context
+    pub flags: i32,
+
+    pub padding: [u8; 24],
+}
+assert_eq_size!(MessageSigCalls, MessagePayload);
+impl Payload for MessageSigCalls {}
+
+/// the size of the FPU state saved with FXSAVE
+pub const FPU_XFP_SIZE: usize = 512;
+
+/// set in `sc_flags` if the FPU state in the sigcontext is valid.
+/// In Minix, it's one of the `p_misc_flags` of a process
+pub const MF_FPU_INITIALIZED: i32 = 0x1000;
+
+/// the state of the FPU after FNINIT, with the SSE exceptions masked.
+/// A signal handler starts with a clean FPU
+pub fn fpu_init_state() -> [u8; FPU_XFP_SIZE] {
+    let mut state = [0u8; FPU_XFP_SIZE];
+    state[0..2].copy_from_slice(&0x037fu16.to_ne_bytes()); // control word
+    state[24..28].copy_from_slice(&0x1f80u32.to_ne_bytes()); // MXCSR
+    state
+}
+
+/// the value of `sc_magic` in a valid sigcontext
+pub const SC_MAGIC: i32 = 0xc0ffee1;
+
+/// the user flags of the eflags register, which
+/// a process may restore with SYS_SIGRETURN
+pub const X86_FLAGS_USER: u32 = 0x0001 // carry
+    | 0x0004 // parity
+    | 0x0010 // auxiliary carry
+    | 0x0040 // zero
+    | 0x0080 // sign
+    | 0x0400 // direction
+    | 0x0800; // overflow
+
+/// the signal details PM passes with SYS_SIGSEND,
+/// defined in `include/signal.h`
+#[repr(C)]
+#[derive(Debug)]
+pub struct SigMsg {
+    pub sm_signo: i32,      // signal number being caught
+    pub sm_mask: SigSet,    // mask to restore when handler returns
+    pub sm_sighandler: u32, // address of handler
+    pub sm_sigreturn: u32,  // address of _sigreturn in C library
+    pub sm_stkptr: u32,     // user stack pointer
+}
+
+/// the state of a process saved when it catches a signal,
+/// defined in `sys/arch/i386/include/signal.h`
+#[repr(C)]
+#[derive(Clone)]
+pub struct SigContext {
+    pub sc_gs: i32,
+    pub sc_fs: i32,
+    pub sc_es: i32,
+    pub sc_ds: i32,
+    pub sc_edi: i32,
+    pub sc_esi: i32,
+    pub sc_ebp: i32,
+    pub sc_ebx: i32,
+    pub sc_edx: i32,
+    pub sc_ecx: i32,
+    pub sc_eax: i32,
+    pub sc_eip: i32,
+    pub sc_cs: i32,
+    pub sc_eflags: i32,
+    pub sc_esp: i32,
+    pub sc_ss: i32,
+
+    pub sc_onstack: i32,  // sigstack state to restore
+    pub __sc_mask13: i32, // signal mask to restore (old style)
+
+    pub sc_trapno: i32,
+    pub sc_err: i32,
+
+    pub sc_mask: SigSet, // signal mask to restore (new style)
+    pub sc_fpu_state: [u8; FPU_XFP_SIZE],
+    pub sc_magic: i32,
+    pub sc_flags: i32,
+    pub trap_style: i32,
+}
+
+/// the frame built on the stack of a process, to call
+/// its signal handler. Defined in `sys/arch/i386/include/frame.h`
+#[repr(C)]
+pub struct SigframeSigcontext {
+    pub sf_ra_sigreturn: u32, // first return address
+    pub sf_signum: i32,       // "signum" argument for handler
+    pub sf_code: i32,         // "code" argument for handler
+    pub sf_scp: u32,          // "scp" argument for handler
+    pub sf_fp: u32,           // saved frame pointer
+    pub sf_ra: u32,           // second return address
+    pub sf_scpcopy: u32,      // the argument of sigreturn()
+    pub sf_sc: SigContext,    // actual saved context
+}
+assert_eq_size!(SigframeSigcontext, [u8; 648]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sig_set_test() {
+        let mut set = SigSet::default();
+        set.add(SIGHUP);
+        set.add(SIGKSIG);
+        assert!(set.is_member(SIGHUP) && set.is_member(SIGKSIG));
+        assert!(!set.is_member(SIGINT));
+
+        // signal numbers outside the set are ignored
+        for sig in [0, -1, i32::MIN, 129, i32::MAX] {
+            set.add(sig);
+            set.del(sig);
+            assert!(!set.is_member(sig));
+        }
+
+        set.del(SIGHUP);
+        set.del(SIGKSIG);
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use nix::{
+    errno::Errno,
+    sys::{
+        signal::Signal::{SIGSEGV, SIGSTOP},
+        wait::WaitStatus,
+    },
+};
+
+use crate::utils::{ChildWaiter, Endpoint, Instruction, MinixProcessTable};
+use crate::{clock_task, ipc, sys};
+
+/// the path of a test binary, built from `test_src`
+pub fn test_bin(name: &str) -> String {
+    format!("{}/test_bin/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// runs the processes in the table, and the alarms they set, like
+/// `main_loop`, until all of them are gone. Returns the exit status
+/// of each process, sorted by endpoint
+pub fn run_processes(process_table: &mut MinixProcessTable) -> Vec<(Endpoint, i32)> {
+    run_processes_with(process_table, |_, _| true)
+}
+
+/// like `run_processes`, but calls `hook` with each event of the processes
+/// before handling it. If `hook` returns false, the event isn't handled,
+/// which is left to the hook, or until later
+pub fn run_processes_with(
+    process_table: &mut MinixProcessTable,
+    mut hook: impl FnMut(&WaitStatus, &mut MinixProcessTable) -> bool,
+) -> Vec<(Endpoint, i32)> {
+    let waiter = ChildWaiter::new().unwrap();
+
+    // the processes killed when they were cleared are waited for
+    // too, so that they don't show up in the next test
+    let mut statuses = vec![];
+    loop {
+        clock_task::expire_timers(process_table).unwrap();
+        let status = match waiter.wait(clock_task::next_timeout(process_table)) {
+            Ok(Some(status)) => status,
+            Ok(None) => continue,
+            Err(nix::Error::Sys(Errno::ECHILD)) => break,
+            Err(e) => panic!("waiting for the processes failed: {}", e),
+        };
+
+        if hook(&status, process_table) {
+            statuses.extend(handle_event(status, process_table));
+        }
+    }
+
+    assert!(process_table.is_empty());
+    statuses.sort_unstable();
+    statuses
+}
+
+/// handles the event of a process like `main_loop`. An exited process
+/// is cleared, and its endpoint and exit status are returned.
+/// A process killed while it's still in the table crashed
+fn handle_event(
+    status: WaitStatus,
+    process_table: &mut MinixProcessTable,
+) -> Option<(Endpoint, i32)> {
+    match status {
+        WaitStatus::Stopped(pid, SIGSEGV) => {
+            let endpoint = process_table.pid_to_endpoint(pid).unwrap();
+            match process_table[endpoint].read_instruction().unwrap() {
+                Instruction::Int(0x20) => sys::do_kernel_call(endpoint, process_table).unwrap(),
+                Instruction::Int(0x21) => ipc::do_ipc(endpoint, process_table).unwrap(),
+                _ => panic!("process {} segfaulted", endpoint),
+            }
+        }
+        WaitStatus::Stopped(pid, SIGSTOP) => {
+            if let Some(process) = process_table.get_by_pid(pid) {
+                process.cont().unwrap();
+            }
+        }
+        WaitStatus::Stopped(pid, sig) => {
+            let endpoint = process_table.pid_to_endpoint(pid).unwrap();
+            sys::cause_linux_sig(endpoint, sig, process_table).unwrap();
+        }
+        WaitStatus::Exited(pid, status) => {
+            let endpoint = process_table.pid_to_endpoint(pid).unwrap();
+            sys::clear_endpoint(endpoint, process_table).unwrap();
+            return Some((endpoint, status));
+        }
+        WaitStatus::Signaled(pid, sig, _) => {
+            if let Some(endpoint) = process_table.pid_to_endpoint(pid) {
+                panic!("process {} was killed by {}", endpoint, sig);
+            }
+        }
+        status => panic!("unexpected status: {:?}", status),
+    }
+
+    None
+}
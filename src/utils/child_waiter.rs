@@ -0,0 +1,139 @@
+use std::{
+    os::{raw::c_int, unix::io::RawFd},
+    sync::atomic::{AtomicI32, Ordering},
+    time::Duration,
+};
+
+use nix::{
+    errno::Errno,
+    fcntl::OFlag,
+    libc,
+    poll::{poll, PollFd, PollFlags},
+    sys::{
+        signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal::SIGCHLD},
+        wait::{waitpid, WaitPidFlag, WaitStatus},
+    },
+    unistd::{close, pipe2, read, write},
+};
+
+/// the write end of the pipe the SIGCHLD handler writes to
+static SIGCHLD_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+extern "C" fn on_sigchld(_: c_int) {
+    // the handler may interrupt code checking errno, so preserve it
+    let errno = nix::errno::errno();
+    let fd = SIGCHLD_PIPE.load(Ordering::SeqCst);
+    if fd >= 0 {
+        // if the pipe is full, there's a wakeup pending already
+        let _ = write(fd, &[0]);
+    }
+    unsafe { *libc::__errno_location() = errno };
+}
+
+/// waits for events of the child processes, like `wait()`,
+/// but with a timeout. The SIGCHLD signals are turned
+/// into readable data on a pipe, which can be polled.
+/// There's only one SIGCHLD handler, so only one waiter
+/// may exist at a time
+pub struct ChildWaiter {
+    read_fd: RawFd,
+    write_fd: RawFd,
+}
+
+impl ChildWaiter {
+    /// creates the waiter. Fails with EBUSY if another one exists
+    pub fn new() -> Result<Self, nix::Error> {
+        let (read_fd, write_fd) = pipe2(OFlag::O_NONBLOCK | OFlag::O_CLOEXEC)?;
+        if SIGCHLD_PIPE
+            .compare_exchange(-1, write_fd, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            let _ = close(read_fd);
+            let _ = close(write_fd);
+            return Err(nix::Error::Sys(Errno::EBUSY));
+        }
+
+        // SA_RESTART, so the signal doesn't interrupt the other system calls.
+        // SA_NOCLDSTOP is unset, since we're interested in the children stopping
+        let action = SigAction::new(
+            SigHandler::Handler(on_sigchld),
+            SaFlags::SA_RESTART,
+            SigSet::empty(),
+        );
+        unsafe { sigaction(SIGCHLD, &action)? };
+
+        Ok(Self { read_fd, write_fd })
+    }
+
+    /// waits for a child process to change state, for at most `timeout`
+    /// (or indefinitely if `None`). Returns `None` if the time ran out
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<Option<WaitStatus>, nix::Error> {
+        // check for the events that happened already, so we don't
+        // miss any that came before the last wakeup was consumed
+        self.drain();
+        match waitpid(None, Some(WaitPidFlag::WNOHANG))? {
+            WaitStatus::StillAlive => {}
+            status => return Ok(Some(status)),
+        }
+
+        // round up, so we don't wake up before the timeout
+        let timeout = match timeout {
+            Some(timeout) => {
+                let millis = timeout.as_nanos().div_ceil(1_000_000);
+                millis.min(c_int::MAX as u128) as c_int
+            }
+            None => -1,
+        };
+        let mut fds = [PollFd::new(self.read_fd, PollFlags::POLLIN)];
+        match poll(&mut fds, timeout) {
+            Ok(_) | Err(nix::Error::Sys(Errno::EINTR)) => {}
+            Err(e) => return Err(e),
+        }
+
+        // the child may have changed state, or the time ran out
+        match waitpid(None, Some(WaitPidFlag::WNOHANG))? {
+            WaitStatus::StillAlive => Ok(None),
+            status => Ok(Some(status)),
+        }
+    }
+
+    /// consumes the pending wakeups
+    fn drain(&self) {
+        let mut buf = [0u8; 64];
+        while let Ok(n) = read(self.read_fd, &mut buf) {
+            if n == 0 {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for ChildWaiter {
+    fn drop(&mut self) {
+        // the handler stays installed, but doesn't write anywhere
+        SIGCHLD_PIPE.store(-1, Ordering::SeqCst);
+        let _ = close(self.read_fd);
+        let _ = close(self.write_fd);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nix::errno::Errno;
+
+    use super::ChildWaiter;
+
+    #[test]
+    fn single_waiter_test() {
+        // a second waiter would take over the SIGCHLD pipe of the first
+        let waiter = ChildWaiter::new().unwrap();
+        assert!(matches!(
+            ChildWaiter::new(),
+            Err(nix::Error::Sys(Errno::EBUSY))
+        ));
+
+        // once the first one is gone, there may be a new one
+        drop(waiter);
+        let _waiter = ChildWaiter::new().unwrap();
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    fs::File,
+    os::unix::fs::FileExt,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use super::{as_buf_u8, Clockinfo, SharedMemory};
+
+/// how often the clock information in the shared memory is refreshed
+const CLOCKINFO_UPDATE_PERIOD: Duration = Duration::from_millis(1);
+
+/// the real time is carried into the boot time once it reaches this many
+/// ticks, so that it fits the 32-bit tick counts of the processes
+const REALTIME_CARRY: u64 = 1 << 31;
+
+/// the emulated system clock, counting
+/// ticks (at `crate::HZ`) since the emulator started
+#[derive(Clone)]
+pub struct Clock {
+    boot: Instant,
+    // shared with the thread updating the clock information
+    wall: Arc<Mutex<WallClock>>,
+}
+
+/// the virtual wall clock, which the processes can set
+/// and adjust, without changing the host's clock
+struct WallClock {
+    boottime: u64,      // seconds since the UNIX epoch, at boot
+    realtime_base: u64, // the real time in ticks since `boottime`, at `base_uptime`
+    base_uptime: u64,   // the uptime the real time was last set at
+    adjtime_delta: i64, // the ticks left to adjust the real time by, at `base_uptime`
+}
+
+impl WallClock {
+    /// the ticks of `adjtime_delta` applied after `elapsed` ticks.
+    /// Like in Minix, one tick is applied every other tick
+    fn adjustment(&self, elapsed: u64) -> i64 {
+        let applied = (elapsed / 2).min(self.adjtime_delta.unsigned_abs()) as i64;
+        applied * self.adjtime_delta.signum()
+    }
+
+    fn realtime(&self, uptime: u64) -> u64 {
+        let elapsed = uptime.saturating_sub(self.base_uptime);
+        (self.realtime_base + elapsed).wrapping_add(self.adjustment(elapsed) as u64)
+    }
+
+    /// starts counting from `uptime`, so the state can be changed
+    fn rebase(&mut self, uptime: u64) {
+        let elapsed = uptime.saturating_sub(self.base_uptime);
+        self.realtime_base = self.realtime(uptime);
+        self.adjtime_delta -= self.adjustment(elapsed);
+        self.base_uptime = uptime;
+    }
+
+    /// moves the whole seconds of a large real time into `boottime`,
+    /// keeping the real time bounded, like in Minix
+    fn normalize(&mut self, uptime: u64) {
+        if self.realtime(uptime) < REALTIME_CARRY {
+            return;
+        }
+        let hz = crate::HZ as u64;
+        self.rebase(uptime);
+        self.boottime += self.realtime_base / hz;
+        self.realtime_base %= hz;
+    }
+
+    fn set_realtime(&mut self, realtime: u64, uptime: u64) {
+        self.rebase(uptime);
+        self.realtime_base = realtime;
+    }
+
+    fn set_adjtime_delta(&mut self, ticks: i64, uptime: u64) {
+        self.rebase(uptime);
+        self.adjtime_delta = ticks;
+    }
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        let boot = Instant::now();
+        let wall = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let wall = WallClock {
+            boottime: wall.as_secs(),
+            realtime_base: ticks(wall.subsec_nanos() as u128),
+            base_uptime: 0,
+            adjtime_delta: 0,
+        };
+
+        Self {
+            boot,
+            wall: Arc::new(Mutex::new(wall)),
+        }
+    }
+
+    /// returns the number of clock ticks since boot,
+    /// derived from the host's monotonic clock
+    pub fn uptime(&self) -> u64 {
+        ticks(self.boot.elapsed().as_nanos())
+    }
+
+    /// returns the boot time, in seconds since the UNIX epoch,
+    /// and the real time, in clock ticks since the boot time.
+    /// The boot time is derived from the host's wall clock, unless set
+    /// by a process, and advances as whole seconds of real time pass
+    pub fn wall_time(&self) -> (u64, u64) {
+        let uptime = self.uptime();
+        let mut wall = self.wall.lock().unwrap();
+        wall.normalize(uptime);
+        (wall.boottime, wall.realtime(uptime))
+    }
+
+    /// returns the boot time, see `wall_time`
+    pub fn boottime(&self) -> u64 {
+        self.wall_time().0
+    }
+
+    pub fn set_boottime(&self, boottime: u64) {
+        self.wall.lock().unwrap().boottime = boottime;
+    }
+
+    pub fn set_realtime(&self, realtime: u64) {
+        let uptime = self.uptime();
+        self.wall.lock().unwrap().set_realtime(realtime, uptime);
+    }
+
+    /// gradually adjusts the real time by `ticks`, like `adjtime()`
+    pub fn set_adjtime_delta(&self, ticks: i64) {
+        let uptime = self.uptime();
+        self.wall.lock().unwrap().set_adjtime_delta(ticks, uptime);
+    }
+
+    /// returns the ticks left to adjust the real time by
+    #[cfg(test)]
+    pub fn adjtime_delta(&self) -> i64 {
+        let uptime = self.uptime();
+        let mut wall = self.wall.lock().unwrap();
+        wall.rebase(uptime);
+        wall.adjtime_delta
+    }
+
+    /// the current clock information, as seen by the processes.
+    /// Like in Minix, the uptime is 32-bit and wraps around,
+    /// while the real time is kept bounded
+    pub fn clockinfo(&self) -> Clockinfo {
+        let (boottime, realtime) = self.wall_time();
+        Clockinfo {
+            boottime: boottime as u32,
+            uptime: self.uptime() as u32,
+            realtime: realtime as u32,
+            ..Clockinfo::default()
+        }
+    }
+}
+
+/// converts a time in nanoseconds to clock ticks
+pub fn ticks(nanos: u128) -> u64 {
+    (nanos * crate::HZ as u128 / 1_000_000_000) as u64
+}
+
+/// converts a number of clock ticks to the time they take
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    let nanos = ticks as u128 * 1_000_000_000 / crate::HZ as u128;
+    Duration::from_nanos(nanos as u64)
+}
+
+/// keeps the clock information at `offset` in the shared memory
+/// up to date, refreshing it periodically from a separate thread
+pub fn spawn_clockinfo_updater(
+    clock: Clock,
+    shared: &SharedMemory,
+    offset: usize,
+) -> Result<(), nix::Error> {
+    let file: File = shared.try_clone_file()?;
+
+    // the loop doesn't allocate, so the thread can't hold
+    // the allocator's lock when we fork a new process
+    thread::spawn(move || loop {
+        let clockinfo: [u8; std::mem::size_of::<Clockinfo>()] = as_buf_u8(&clock.clockinfo());
+        let _ = file.write_at(&clockinfo, offset as u64);
+        thread::sleep(CLOCKINFO_UPDATE_PERIOD);
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wall_clock_test() {
+        let mut wall = WallClock {
+            boottime: 0,
+            realtime_base: 10,
+            base_uptime: 0,
+            adjtime_delta: 0,
+        };
+        assert_eq!(wall.realtime(100), 110);
+
+        // setting the time doesn't affect the uptime it's counted from
+        wall.set_realtime(1000, 100);
+        assert_eq!(wall.realtime(150), 1050);
+
+        // adjusting forward applies a tick every other tick, until done
+        wall.set_adjtime_delta(20, 200);
+        assert_eq!(wall.realtime(200), 1100);
+        assert_eq!(wall.realtime(210), 1115);
+        assert_eq!(wall.realtime(300), 1220);
+
+        // changing the adjustment keeps what was applied so far
+        wall.set_adjtime_delta(-10, 210);
+        assert_eq!(wall.realtime(210), 1115);
+        assert_eq!(wall.realtime(300), 1195);
+    }
+
+    #[test]
+    fn realtime_carry_test() {
+        let hz = crate::HZ as u64;
+        let mut wall = WallClock {
+            boottime: 1000,
+            realtime_base: hz / 2,
+            base_uptime: 0,
+            adjtime_delta: 0,
+        };
+
+        // small real times are kept as they are
+        wall.normalize(hz);
+        assert_eq!((wall.boottime, wall.realtime(hz)), (1000, hz + hz / 2));
+
+        // past 256 seconds, the 32-bit real time would wrap around,
+        // so the whole seconds are carried into the boot time
+        let uptime = 300 * hz;
+        wall.normalize(uptime);
+        assert_eq!((wall.boottime, wall.realtime(uptime)), (1300, hz / 2));
+        assert_eq!(wall.realtime(uptime + hz), hz + hz / 2);
+    }
+
+    #[test]
+    fn clockinfo_wrap_test() {
+        let mut clock = Clock::new();
+        let boottime = clock.boottime();
+        clock.boot -= Duration::from_secs(300);
+
+        // the time the processes compute from the clock
+        // information keeps going forward, past 256 seconds
+        let time = |clockinfo: Clockinfo| {
+            clockinfo.boottime as u64 * crate::HZ as u64 + clockinfo.realtime as u64
+        };
+        let first = clock.clockinfo();
+        thread::sleep(10 * CLOCKINFO_UPDATE_PERIOD);
+        let second = clock.clockinfo();
+
+        assert!((first.realtime as u64) < REALTIME_CARRY);
+        assert!(first.boottime as u64 >= boottime + 300);
+        assert!(time(second) > time(first));
+    }
+
+    #[test]
+    fn clockinfo_updater_test() {
+        use std::mem::size_of;
+
+        let clock = Clock::new();
+        let shared = SharedMemory::new("clock_test", size_of::<Clockinfo>()).unwrap();
+        spawn_clockinfo_updater(clock.clone(), &shared, 0).unwrap();
+
+        let file = shared.try_clone_file().unwrap();
+        let read_clockinfo = || {
+            let mut buf = [0u8; size_of::<Clockinfo>()];
+            file.read_at(&mut buf, 0).unwrap();
+            unsafe { std::mem::transmute::<_, Clockinfo>(buf) }
+        };
+
+        thread::sleep(10 * CLOCKINFO_UPDATE_PERIOD);
+        let first = read_clockinfo();
+        thread::sleep(10 * CLOCKINFO_UPDATE_PERIOD);
+        let second = read_clockinfo();
+
+        assert_eq!(first.hz, crate::HZ);
+        assert_eq!(first.boottime as u64, clock.boottime());
+        assert!(first.uptime > 0 && second.uptime > first.uptime);
+        assert!(second.realtime >= second.uptime);
+    }
+}
@@ -76,6 +76,13 @@ impl MessageQueue {
 
         result
     }
+
+    /// removes all messages sent by `sender` from the queue
+    pub fn remove(&mut self, sender: Endpoint) {
+        self.data
+            .retain(|entry| matches!(entry, Some(entry) if entry.sender != sender));
+        self.some_count = self.data.len();
+    }
 }
 
 struct QueueEntry {
@@ -1,19 +1,25 @@
-use crate::sys::Priv;
+use crate::sys::{signal::FPU_XFP_SIZE, Priv, SigSet};
 
 use super::{message_queue::MessageQueue, Endpoint, Message, SharedMemory, MESSAGE_SIZE};
-use nix::libc::user_regs_struct;
+use nix::errno::Errno;
+use nix::libc::{self, user_fpregs_struct, user_regs_struct};
 use nix::sys::ptrace;
 use nix::sys::signal::{kill, Signal};
 use nix::sys::wait::{waitpid, WaitStatus};
 use nix::unistd::execv;
 use nix::unistd::fork;
 use nix::unistd::Pid;
+use nix::unistd::{sysconf, SysconfVar};
 use std::{
+    cell::Cell,
     ffi::{c_void, CString},
     mem::size_of_val,
     ptr::slice_from_raw_parts,
 };
 
+// PTRACE_GETFPREGS reads the registers as saved by FXSAVE
+assert_eq_size!(user_fpregs_struct, [u8; FPU_XFP_SIZE]);
+
 #[derive(Clone, Copy)]
 pub enum ProcessState {
     Running,
@@ -22,6 +28,27 @@ pub enum ProcessState {
     SendReceiving(Endpoint),
 }
 
+impl ProcessState {
+    /// returns the endpoint the process is blocked on,
+    /// or NONE if it's not blocked (the `P_BLOCKEDON` macro in Minix)
+    pub fn blocked_on(&self) -> Endpoint {
+        match *self {
+            ProcessState::Running => super::endpoint::NONE,
+            ProcessState::Sending(e)
+            | ProcessState::Receiving(e)
+            | ProcessState::SendReceiving(e) => e,
+        }
+    }
+
+    /// checks if the process is blocked on sending a message
+    pub fn is_sending(&self) -> bool {
+        matches!(
+            self,
+            ProcessState::Sending(_) | ProcessState::SendReceiving(_)
+        )
+    }
+}
+
 pub enum Instruction {
     Int(u8), // programmable interrupt
     Other,
@@ -32,13 +59,27 @@ pub struct MinixProcess {
     pid: Pid,
     pub state: ProcessState,
     pub queue: MessageQueue,
+    /// set while the process waits for the reply in SENDREC
+    /// (the `MF_REPLY_PEND` flag in Minix)
     pub reply_pending: bool,
     pub name: String,
     pub s_flags: u16,
     pub privileges: Priv,
-    pub notify_pending: Vec<Endpoint>,
-    pub async_pending: Vec<Endpoint>,
     pub minix_kerninfo_addr: Option<u32>,
+    /// the address of the message buffer of the
+    /// current ipc call (`p_delivermsg_vir` in Minix)
+    pub delivermsg_vir: u64,
+    /// the RTS_SIGNALED and RTS_SIG_PENDING flags
+    /// of the process's `p_rts_flags` in Minix
+    pub rts_flags: u32,
+    /// the kernel signals waiting for the signal manager
+    pub pending_signals: SigSet,
+    /// set when the process should be running, but is held
+    /// stopped until its signals are handled
+    signal_stopped: Cell<bool>,
+    /// set when the full context of the process was set
+    /// while it was stopped, see `set_context`
+    context_set: Cell<bool>,
 }
 
 impl MinixProcess {
@@ -64,10 +105,13 @@ impl MinixProcess {
                     reply_pending: false,
                     name: path.to_string(),
                     s_flags: 0u16,
-                    privileges: Priv::default(),
-                    notify_pending: vec![],
-                    async_pending: vec![],
+                    privileges: Priv::user(),
                     minix_kerninfo_addr: None,
+                    delivermsg_vir: 0,
+                    rts_flags: 0,
+                    pending_signals: SigSet::default(),
+                    signal_stopped: Cell::new(false),
+                    context_set: Cell::new(false),
                 };
 
                 // allocate memory for and set the ps_strings struct in child
@@ -98,8 +142,8 @@ impl MinixProcess {
             }
             Ok(Child) => {
                 ptrace::traceme().unwrap();
-                execv::<&CString>(&CString::new(path).unwrap(), &[]).unwrap();
-                unreachable!()
+                let err = execv::<&CString>(&CString::new(path).unwrap(), &[]).unwrap_err();
+                panic!("execv failed: {}", err)
             }
             Err(e) => Err(e),
         }
@@ -129,16 +173,13 @@ impl MinixProcess {
     /// reads `len` bytes from an address
     /// in the traced process's memory
     pub fn read_buf_u8(&self, addr: u64, len: usize) -> Result<Vec<u8>, nix::Error> {
-        let len64 = (len + 7) / 8; // ceil(len/8)
-        let mut buf: Vec<u8> = (0..len64)
-            .map(|idx| {
-                let addr = addr + 8 * idx as u64;
-                self.read(addr).unwrap() // issue with error handling here
-            })
-            .flat_map(|word| {
-                let bytes = unsafe { std::mem::transmute::<u64, [u8; 8]>(word) };
-                std::array::IntoIter::new(bytes)
-            })
+        let len64 = len.div_ceil(8);
+        let words = (0..len64)
+            .map(|idx| self.read(addr + 8 * idx as u64))
+            .collect::<Result<Vec<u64>, nix::Error>>()?;
+        let mut buf: Vec<u8> = words
+            .iter()
+            .flat_map(|word| IntoIterator::into_iter(word.to_ne_bytes()))
             .collect();
 
         buf.resize(len, 0);
@@ -186,12 +227,14 @@ impl MinixProcess {
         };
         self.write_buf(addr, data_u64)?;
         if data.len() - 8 * len_64 > 0 {
-            let mut rest = [0u8; 8];
+            // keep the bytes following the data in the last word
+            let rest_addr = addr + (8 * len_64 as u64);
+            let mut rest = self.read(rest_addr)?.to_ne_bytes();
             for (idx, &val) in data[8 * len_64..].iter().enumerate() {
                 rest[idx] = val
             }
-            let val_64: u64 = unsafe { std::mem::transmute(rest) };
-            self.write(addr + (8 * len_64 as u64), val_64)?
+            let val_64 = u64::from_ne_bytes(rest);
+            self.write(rest_addr, val_64)?
         }
         Ok(())
     }
@@ -237,19 +280,125 @@ impl MinixProcess {
         Ok(result)
     }
 
-    /// cause a signal in the minix process
-    /// TODO: emulate how minix handles signals, by either
-    /// making pm cause a signal in a user process,
-    /// or message passing in servers/drivers
+    /// returns the user and system time the process used,
+    /// in clock ticks (at `crate::HZ`). The times are those
+    /// Linux accounts to the process, read from `/proc/<pid>/stat`
+    pub fn cpu_times(&self) -> Result<(u64, u64), nix::Error> {
+        let no_process = nix::Error::Sys(nix::errno::Errno::ESRCH);
+        let stat =
+            std::fs::read_to_string(format!("/proc/{}/stat", self.pid)).map_err(|_| no_process)?;
+
+        // the name of the process (in parentheses) may contain spaces,
+        // so we start after it, with the 3rd field (the state).
+        // The user and system times are the 14th and 15th fields
+        let fields: Vec<&str> = stat
+            .rsplit_once(')')
+            .ok_or(no_process)?
+            .1
+            .split_whitespace()
+            .collect();
+        let parse = |field: usize| -> Result<u64, nix::Error> {
+            fields
+                .get(field - 3)
+                .and_then(|value| value.parse().ok())
+                .ok_or(no_process)
+        };
+        let (utime, stime) = (parse(14)?, parse(15)?);
+
+        // Linux reports the times in its own clock ticks
+        let clk_tck = sysconf(SysconfVar::CLK_TCK)?.ok_or(no_process)? as u64;
+        let to_ticks = |time: u64| time * crate::HZ as u64 / clk_tck;
+
+        Ok((to_ticks(utime), to_ticks(stime)))
+    }
+
+    /// resume the process, delivering the Linux signal to it.
+    /// Minix signals are caused with `sys::cause_sig` instead
     pub fn cause_signal(&self, signal: impl Into<Option<Signal>>) -> Result<(), nix::Error> {
         ptrace::cont(self.pid, signal)
     }
 
-    /// resume stopped process
+    /// stops the running process, so it can be held stopped until
+    /// its signals are handled, and waits until it's stopped.
+    /// It may stop for another reason first (like an ipc call trap),
+    /// which is returned to be handled by the caller
+    pub fn stop(&self) -> Result<WaitStatus, nix::Error> {
+        kill(self.pid, Signal::SIGSTOP)?;
+        waitpid(self.pid, None)
+    }
+
+    /// resume stopped process. A process with signals
+    /// pending for its signal manager is held stopped,
+    /// until `resume_signaled` is called
     pub fn cont(&self) -> Result<(), nix::Error> {
+        if self.rts_flags & rts_flags::RTS_SIG_PENDING != 0 {
+            self.signal_stopped.set(true);
+            return Ok(());
+        }
+        self.context_set.set(false);
         ptrace::cont(self.pid, None)
     }
 
+    /// is the process held stopped, until its signals are handled?
+    pub fn is_signal_stopped(&self) -> bool {
+        self.signal_stopped.get()
+    }
+
+    /// reads the FPU and SSE registers of the stopped process,
+    /// in the format saved by the FXSAVE instruction
+    pub fn get_fpregs(&self) -> Result<[u8; FPU_XFP_SIZE], nix::Error> {
+        let mut fpregs = [0u8; FPU_XFP_SIZE];
+        let result = unsafe {
+            libc::ptrace(
+                libc::PTRACE_GETFPREGS,
+                self.pid.as_raw(),
+                std::ptr::null_mut::<c_void>(),
+                fpregs.as_mut_ptr() as *mut user_fpregs_struct,
+            )
+        };
+        Errno::result(result)?;
+        Ok(fpregs)
+    }
+
+    /// sets the FPU and SSE registers of the stopped process,
+    /// from the format saved by the FXSAVE instruction
+    pub fn set_fpregs(&self, fpregs: &[u8; FPU_XFP_SIZE]) -> Result<(), nix::Error> {
+        let result = unsafe {
+            libc::ptrace(
+                libc::PTRACE_SETFPREGS,
+                self.pid.as_raw(),
+                std::ptr::null_mut::<c_void>(),
+                fpregs.as_ptr() as *const user_fpregs_struct,
+            )
+        };
+        Errno::result(result)?;
+        Ok(())
+    }
+
+    /// sets the registers of the stopped process to a full context
+    /// (like when returning from a signal handler). Until the process
+    /// is resumed, the ipc calls don't change its registers anymore
+    pub fn set_context(&self, regs: user_regs_struct) -> Result<(), nix::Error> {
+        self.set_regs(regs)?;
+        self.context_set.set(true);
+        Ok(())
+    }
+
+    /// was the full context of the process set, since it was stopped?
+    /// Like the MF_CONTEXT_SET flag in Minix
+    pub fn is_context_set(&self) -> bool {
+        self.context_set.get()
+    }
+
+    /// resumes the process, if it was held stopped by `cont`
+    /// while its signals were handled
+    pub fn resume_signaled(&self) -> Result<(), nix::Error> {
+        if self.signal_stopped.replace(false) {
+            self.cont()?;
+        }
+        Ok(())
+    }
+
     /// do a Linux system call in the minix process
     pub fn _do_syscall(&self, syscall_number: u64, args: &[u64]) -> Result<u64, nix::Error> {
         // save the register values to be restored
@@ -304,6 +453,78 @@ impl MinixProcess {
         Ok(after_regs.rax)
     }
 
+    /// duplicates the (stopped) process, by injecting a Linux `fork`
+    /// system call into it. The child is traced by us
+    /// and left stopped, in the same state as the parent.
+    pub fn fork(&self) -> Result<Self, nix::Error> {
+        use ptrace::Options;
+
+        // have the kernel attach us to the forked child
+        ptrace::setoptions(self.pid, Options::PTRACE_O_TRACEFORK)?;
+
+        let old_regs = self.get_regs()?;
+        let mut regs = old_regs;
+        regs.rax = 2; // fork syscall number
+        self.set_regs(regs)?;
+
+        let instruction_addr = regs.rip;
+        let old_instruction = self.read(instruction_addr)?;
+        self.write(instruction_addr, 0x80CD)?; // int 0x80
+
+        // stop at the syscall entry, then at the fork event
+        ptrace::syscall(self.pid, None)?;
+        let _status = waitpid(self.pid, None)?;
+        ptrace::syscall(self.pid, None)?;
+
+        let child = match waitpid(self.pid, None)? {
+            WaitStatus::PtraceEvent(_, _, event)
+                if event == ptrace::Event::PTRACE_EVENT_FORK as i32 =>
+            {
+                Pid::from_raw(ptrace::getevent(self.pid)? as i32)
+            }
+            _ => {
+                // fork failed, the process is stopped after the syscall
+                self.write(instruction_addr, old_instruction)?;
+                self.set_regs(old_regs)?;
+                ptrace::setoptions(self.pid, Options::empty())?;
+                return Err(nix::Error::Sys(nix::errno::Errno::EAGAIN));
+            }
+        };
+
+        // finish the syscall in the parent, and restore its state
+        ptrace::syscall(self.pid, None)?;
+        let _status = waitpid(self.pid, None)?;
+        self.write(instruction_addr, old_instruction)?;
+        self.set_regs(old_regs)?;
+        ptrace::setoptions(self.pid, Options::empty())?;
+
+        // the child starts stopped with SIGSTOP; its memory is a copy
+        // of the parent's, so it still holds the injected instruction
+        let _status = waitpid(child, None)?;
+        ptrace::setoptions(child, Options::empty())?;
+
+        let minix_process = Self {
+            pid: child,
+            state: self.state,
+            queue: MessageQueue::new(),
+            reply_pending: self.reply_pending,
+            name: self.name.clone(),
+            s_flags: self.s_flags,
+            privileges: self.privileges.clone(),
+            minix_kerninfo_addr: self.minix_kerninfo_addr,
+            delivermsg_vir: self.delivermsg_vir,
+            rts_flags: 0,
+            pending_signals: SigSet::default(),
+            signal_stopped: Cell::new(false),
+            context_set: Cell::new(false),
+        };
+
+        minix_process.write(instruction_addr, old_instruction)?;
+        minix_process.set_regs(old_regs)?;
+
+        Ok(minix_process)
+    }
+
     /// attaches memory represented by the SharedMemory struct
     /// in the process at the given address.
     /// It's important that the SharedMemory struct is created
@@ -315,10 +536,10 @@ impl MinixProcess {
         let mmap_args: [u32; 6] = [
             addr as u32,       // address to map to
             shared.len as u32, // length of mapped memory
-            0x1,               // file access, set to PROT_READ
-            0x1 | 0x10,        // mmap flags, set to MAP_SHARED | MAP_FIXED
-            shared.fd as u32,  // file descriptor to be mapped
-            0,                 // offset
+            0x1 | 0x4, // file access, set to PROT_READ | PROT_EXEC (for the ipc trampolines)
+            0x1 | 0x10, // mmap flags, set to MAP_SHARED | MAP_FIXED
+            shared.fd as u32, // file descriptor to be mapped
+            0,         // offset
         ];
 
         // get the mmap args as a byte slice
@@ -376,6 +597,12 @@ struct PsStrings {
     ps_nenvstr: u32,
 }
 
+/// This module contains the bits for the rts_flags field
+pub mod rts_flags {
+    pub const RTS_SIGNALED: u32 = 0x10; // set when a new kernel signal arrives
+    pub const RTS_SIG_PENDING: u32 = 0x20; // unready while signal being processed
+}
+
 /// This module contains bits for the s_flags field
 #[allow(dead_code)]
 pub mod priv_flags {
@@ -398,10 +625,11 @@ mod tests {
     use nix::sys::wait::WaitStatus;
 
     use super::MinixProcess;
+    use crate::test_utils::test_bin;
 
     #[test]
     fn do_syscall_test() {
-        let path = format!("{}/test_bin/syscall", env!("CARGO_MANIFEST_DIR"));
+        let path = test_bin("syscall");
         let process = MinixProcess::spawn(&path).unwrap();
 
         match nix::sys::wait::wait().unwrap() {
@@ -420,6 +648,103 @@ mod tests {
         panic!("wrong exit");
     }
 
+    #[test]
+    fn cpu_times_test() {
+        let path = test_bin("syscall");
+        let process = MinixProcess::spawn(&path).unwrap();
+
+        match nix::sys::wait::wait().unwrap() {
+            WaitStatus::Stopped(_, nix::sys::signal::Signal::SIGTRAP) => {
+                // the process has barely run, so it used less than a second
+                let (user_time, system_time) = process.cpu_times().unwrap();
+                assert!(user_time < crate::HZ as u64 && system_time < crate::HZ as u64);
+            }
+            _ => panic!("process wasn't stopped by SIGTRAP"),
+        };
+
+        process.cont().unwrap();
+        match nix::sys::wait::wait().unwrap() {
+            WaitStatus::Exited(_, 0) => {}
+            _ => panic!("wrong exit"),
+        };
+
+        // the process is gone, so its times can't be read
+        assert!(process.cpu_times().is_err());
+    }
+
+    #[test]
+    fn read_buf_u8_test() {
+        let path = test_bin("syscall");
+        let process = MinixProcess::spawn(&path).unwrap();
+
+        match nix::sys::wait::wait().unwrap() {
+            WaitStatus::Stopped(_, nix::sys::signal::Signal::SIGTRAP) => {
+                // reading from an address the process hasn't mapped fails
+                assert!(process.read_buf_u8(0, 16).is_err());
+            }
+            _ => panic!("process wasn't stopped by SIGTRAP"),
+        };
+
+        process.cont().unwrap();
+        match nix::sys::wait::wait().unwrap() {
+            WaitStatus::Exited(_, 0) => {}
+            _ => panic!("wrong exit"),
+        };
+    }
+
+    #[test]
+    fn write_buf_u8_test() {
+        let path = test_bin("syscall");
+        let process = MinixProcess::spawn(&path).unwrap();
+
+        match nix::sys::wait::wait().unwrap() {
+            WaitStatus::Stopped(_, nix::sys::signal::Signal::SIGTRAP) => {
+                // write below the stack pointer, where the process keeps nothing.
+                // Writing a partial word leaves the bytes following it untouched
+                let addr = process.get_regs().unwrap().rsp - 64;
+                process.write_buf_u8(addr, &[0xaa; 16]).unwrap();
+                process.write_buf_u8(addr, &[1, 2, 3]).unwrap();
+                let mut expected = vec![0xaa; 16];
+                expected[..3].copy_from_slice(&[1, 2, 3]);
+                assert_eq!(process.read_buf_u8(addr, 16).unwrap(), expected);
+            }
+            _ => panic!("process wasn't stopped by SIGTRAP"),
+        };
+
+        process.cont().unwrap();
+        match nix::sys::wait::wait().unwrap() {
+            WaitStatus::Exited(_, 0) => {}
+            _ => panic!("wrong exit"),
+        };
+    }
+
+    #[test]
+    fn fork_test() {
+        let path = test_bin("syscall");
+        let process = MinixProcess::spawn(&path).unwrap();
+
+        let child = match nix::sys::wait::wait().unwrap() {
+            WaitStatus::Stopped(_, nix::sys::signal::Signal::SIGTRAP) => process.fork().unwrap(),
+            _ => panic!("process wasn't stopped by SIGTRAP"),
+        };
+        assert_ne!(process.pid(), child.pid());
+
+        // both processes should continue from the same point and exit normally
+        process.cont().unwrap();
+        child.cont().unwrap();
+        let mut exited = 0;
+        while exited < 2 {
+            match nix::sys::wait::wait().unwrap() {
+                WaitStatus::Exited(_, 0) => exited += 1,
+                // the parent is notified about its child exiting
+                WaitStatus::Stopped(pid, nix::sys::signal::Signal::SIGCHLD) => {
+                    nix::sys::ptrace::cont(pid, None).unwrap()
+                }
+                status => panic!("wrong exit: {:?}", status),
+            }
+        }
+    }
+
     #[test]
     fn attach_shared_test() {
         use crate::utils::SharedMemory;
@@ -427,7 +752,7 @@ mod tests {
         let shared_mem = SharedMemory::new("test", std::mem::size_of::<i32>()).unwrap();
         shared_mem.write(0, &42i32).unwrap();
 
-        let path = format!("{}/test_bin/attach", env!("CARGO_MANIFEST_DIR"));
+        let path = test_bin("attach");
         let process = MinixProcess::spawn(&path).unwrap();
 
         let addr = 0xf1002000u32;
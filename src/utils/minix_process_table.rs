@@ -5,47 +5,93 @@ use std::{
     ops::{Index, IndexMut},
 };
 
+use super::endpoint::{
+    endpoint_g, endpoint_p, iskerneln, make_endpoint, ENDPOINT_MAX_GENERATION, NR_TASKS,
+};
 use super::MinixProcess;
-use super::{Endpoint, SharedImage, SharedMemory};
+use super::{Clock, Endpoint, SharedImage, SharedMemory, USERMAPPED_SIZE};
+use crate::sys::{static_priv_id, USER_PRIV_ID};
 
-const MAX_PROCESSES: usize = 256;
+/// the number of user process slots
+pub const NR_PROCS: usize = 256;
+/// kernel tasks come first in the table, like in Minix
+pub const TABLE_SIZE: usize = NR_TASKS as usize + NR_PROCS;
 
 /// a struct containing all running Minix processes, indexed by their endpoints
 pub struct MinixProcessTable {
-    /// the array of all running Minix processes
-    table: [Option<MinixProcess>; MAX_PROCESSES],
+    /// the array of all running Minix processes. The process
+    /// with number `n` is stored at index `n + NR_TASKS`
+    table: [Option<MinixProcess>; TABLE_SIZE],
+    /// the current endpoint generation of each slot in the `table` array
+    generations: [Endpoint; TABLE_SIZE],
     /// a map mapping (Linux) pids to indices in the `table` array
     pid_map: BTreeMap<Pid, usize>,
     /// the values mapped for processes shared with the kernel
     pub usermapped: SharedImage,
     /// shared memory backing the mapping of these values
     pub usermapped_mem: SharedMemory,
+    /// the system clock
+    pub clock: Clock,
 }
 
 impl MinixProcessTable {
     pub fn new() -> Self {
         Self {
             table: array_init(|_| None),
+            generations: [0; TABLE_SIZE],
             pid_map: BTreeMap::new(),
             usermapped: SharedImage::default(),
-            usermapped_mem: SharedMemory::new("minix_usermapped", 4096).unwrap(),
+            usermapped_mem: SharedMemory::new("minix_usermapped", USERMAPPED_SIZE).unwrap(),
+            clock: Clock::new(),
+        }
+    }
+
+    /// returns the index in the `table` array for the process number,
+    /// if the number is in the valid range (the `isokprocn` macro in Minix)
+    fn proc_idx(proc_nr: Endpoint) -> Option<usize> {
+        let idx = proc_nr + NR_TASKS;
+        if idx < 0 || idx as usize >= TABLE_SIZE {
+            return None;
+        }
+        Some(idx as usize)
+    }
+
+    /// returns the index in the `table` array for the endpoint,
+    /// if the endpoint belongs to the current generation of its slot
+    fn slot(&self, endpoint: Endpoint) -> Option<usize> {
+        let idx = Self::proc_idx(endpoint_p(endpoint))?;
+        if self.generations[idx] != endpoint_g(endpoint) {
+            return None;
         }
+        Some(idx)
+    }
+
+    /// returns the endpoint of the process occupying the slot
+    fn slot_endpoint(&self, idx: usize) -> Endpoint {
+        make_endpoint(self.generations[idx], idx as Endpoint - NR_TASKS)
+    }
+
+    /// checks if the endpoint belongs to a living process,
+    /// with the current generation number of its slot
+    /// (the `isokendpt` function in Minix)
+    pub fn isokendpt(&self, endpoint: Endpoint) -> bool {
+        self.get(endpoint).is_some()
     }
 
     pub fn get(&self, endpoint: Endpoint) -> Option<&MinixProcess> {
-        let idx = endpoint as usize;
+        let idx = self.slot(endpoint)?;
         self.table[idx].as_ref()
     }
 
     pub fn get_mut(&mut self, endpoint: Endpoint) -> Option<&mut MinixProcess> {
-        let idx = endpoint as usize;
+        let idx = self.slot(endpoint)?;
         self.table[idx].as_mut()
     }
 
     /// returns a reference to the MinixProcess struct
     /// with the given (Linux) pid
     pub fn get_by_pid(&self, pid: Pid) -> Option<&MinixProcess> {
-        let idx = self.pid_to_endpoint(pid)? as usize;
+        let idx = *self.pid_map.get(&pid)?;
         self.table[idx].as_ref()
     }
 
@@ -53,30 +99,87 @@ impl MinixProcessTable {
     /// with the given (Linux) pid
     #[allow(dead_code)]
     pub fn get_mut_by_pid(&mut self, pid: Pid) -> Option<&mut MinixProcess> {
-        let idx = self.pid_to_endpoint(pid)? as usize;
+        let idx = *self.pid_map.get(&pid)?;
         self.table[idx].as_mut()
     }
 
+    /// returns an iterator over the endpoints of all processes in the table
+    pub fn endpoints(&self) -> impl Iterator<Item = Endpoint> + '_ {
+        self.table
+            .iter()
+            .enumerate()
+            .filter(|(_, process)| process.is_some())
+            .map(move |(idx, _)| self.slot_endpoint(idx))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pid_map.is_empty()
+    }
+
     pub fn pid_to_endpoint(&self, pid: Pid) -> Option<Endpoint> {
-        self.pid_map.get(&pid).map(|v| *v as Endpoint)
+        self.pid_map.get(&pid).map(|&idx| self.slot_endpoint(idx))
+    }
+
+    /// returns the privilege id of the process (`nr_to_id` in Minix).
+    /// Kernel tasks have static privilege ids
+    pub fn priv_id(&self, endpoint: Endpoint) -> Option<usize> {
+        let proc_nr = endpoint_p(endpoint);
+        if iskerneln(proc_nr) && proc_nr >= -NR_TASKS {
+            return Some(static_priv_id(proc_nr) as usize);
+        }
+        self.get(endpoint)
+            .map(|process| process.privileges.s_id as usize)
+    }
+
+    /// returns the endpoint of the process with the given privilege id
+    /// (`id_to_nr` in Minix). User processes share their privilege id,
+    /// so it doesn't identify any single process
+    pub fn id_to_endpoint(&self, id: usize) -> Option<Endpoint> {
+        if id < NR_TASKS as usize {
+            return Some(id as Endpoint - NR_TASKS);
+        }
+        if id == USER_PRIV_ID as usize {
+            return None;
+        }
+        self.endpoints()
+            .find(|&endpoint| self[endpoint].privileges.s_id as usize == id)
+    }
+
+    /// returns the endpoint the next process placed
+    /// in the (user process) slot `proc_nr` will be using
+    pub fn next_endpoint(&self, proc_nr: Endpoint) -> Option<Endpoint> {
+        if proc_nr < 0 {
+            return None;
+        }
+        Some(self.slot_endpoint(Self::proc_idx(proc_nr)?))
     }
 
     pub fn insert(&mut self, proc: MinixProcess, endpoint: Endpoint) -> Result<(), ()> {
-        let idx = endpoint as usize;
+        let idx = Self::proc_idx(endpoint_p(endpoint)).ok_or(())?;
         if self.table[idx].is_some() {
             return Err(());
         }
         let pid = proc.pid();
         self.table[idx] = Some(proc);
+        self.generations[idx] = endpoint_g(endpoint);
         self.pid_map.insert(pid, idx);
         Ok(())
     }
 
+    /// removes the process from the table, and bumps the
+    /// generation of its slot, so that the endpoint is no longer valid
     pub fn remove(&mut self, endpoint: Endpoint) -> Option<MinixProcess> {
-        let idx = endpoint as usize;
+        let idx = self.slot(endpoint)?;
         let process = self.table[idx].take();
         if let Some(process) = process.as_ref() {
             self.pid_map.remove(&process.pid());
+
+            let generation = self.generations[idx] + 1;
+            self.generations[idx] = if generation >= ENDPOINT_MAX_GENERATION {
+                1
+            } else {
+                generation
+            };
         };
         process
     }
@@ -102,8 +205,8 @@ impl IndexMut<Endpoint> for MinixProcessTable {
 // if MinixProcessTable implements some more complex logic
 impl Drop for MinixProcessTable {
     fn drop(&mut self) {
-        for i in 0..MAX_PROCESSES {
-            self.remove(i as Endpoint);
+        for process in self.table.iter_mut() {
+            process.take();
         }
     }
 }
@@ -1,7 +1,17 @@
 use std::mem::size_of;
 
+use super::NR_PROCS;
+use crate::sys::KInfo;
+
 const KERNINFO_MAGIC: u32 = 0xfc3b84bf;
 
+/// bits for the `ki_flags` field of `MinixKerninfo`
+#[allow(dead_code)]
+pub mod ki_flags {
+    pub const MINIX_KIF_IPCVECS: u32 = 1 << 0;
+    pub const MINIX_KIF_USERINFO: u32 = 1 << 1;
+}
+
 /// the minix_kerninfo structure, shared by all processes
 #[repr(C)]
 pub struct MinixKerninfo {
@@ -23,8 +33,7 @@ pub struct MinixKerninfo {
 
 impl Default for MinixKerninfo {
     fn default() -> Self {
-        // init all fields with 0 for now, hope NULLs are ok here
-        // TODO: fix this
+        // the pointers are set up by `SharedImage`
         Self {
             kerninfo_magic: KERNINFO_MAGIC,
             minix_feature_flags: 0,
@@ -68,12 +77,245 @@ impl Default for Clockinfo {
     }
 }
 
+/// the addresses of the ipc functions libc calls through,
+/// defined in `include/minix/ipc.h`
+#[repr(C)]
+#[derive(Default)]
+pub struct MinixIpcvecs {
+    pub send: u32,
+    pub receive: u32,
+    pub sendrec: u32,
+    pub sendnb: u32,
+    pub notify: u32,
+    pub do_kernel_call: u32,
+    pub senda: u32,
+}
+
+const TRAMPOLINE_SIZE: usize = 32;
+type Trampoline = [u8; TRAMPOLINE_SIZE];
+
+/// pads the code of a trampoline with `int3` instructions
+const fn trampoline(code: &[u8]) -> Trampoline {
+    let mut result = [0xcc; TRAMPOLINE_SIZE];
+    let mut i = 0;
+    while i < code.len() {
+        result[i] = code[i];
+        i += 1;
+    }
+    result
+}
+
+// The trampolines follow the `usermapped_*_softint` functions
+// in Minix's `arch/i386/usermapped_glo_ipc.S`. They move the (cdecl)
+// arguments into registers and trap with `int`, which we intercept
+// the same way as the hard-coded ipc and kernel calls.
+// Minix also has `sysenter` and `syscall` variants, but those can't
+// be told apart from Linux system calls, so we only provide these.
+const PROLOGUE: [u8; 4] = [
+    0x55, // push ebp
+    0x89, 0xe5, // mov ebp, esp
+    0x53, // push ebx
+];
+const EPILOGUE: [u8; 3] = [
+    0x5b, // pop ebx
+    0x5d, // pop ebp
+    0xc3, // ret
+];
+
+/// `int f(endpoint_t, message *)` for the SEND, SENDREC and SENDNB calls
+const fn ipc_trampoline(call_nr: u8) -> Trampoline {
+    let [p0, p1, p2, p3] = PROLOGUE;
+    let [e0, e1, e2] = EPILOGUE;
+    trampoline(&[
+        p0, p1, p2, p3, //
+        0x8b, 0x45, 0x08, // mov eax, [ebp+8]     ; endpoint
+        0x8b, 0x5d, 0x0c, // mov ebx, [ebp+12]    ; message
+        0xb9, call_nr, 0, 0, 0, // mov ecx, call_nr
+        0xcd, 0x21, // int 0x21
+        e0, e1, e2,
+    ])
+}
+
+/// `int receive(endpoint_t, message *, int *status)`
+const fn receive_trampoline() -> Trampoline {
+    let [p0, p1, p2, p3] = PROLOGUE;
+    let [e0, e1, e2] = EPILOGUE;
+    trampoline(&[
+        p0, p1, p2, p3, //
+        0x8b, 0x45, 0x08, // mov eax, [ebp+8]     ; source
+        0x8b, 0x5d, 0x0c, // mov ebx, [ebp+12]    ; message
+        0xb9, 0x02, 0, 0, 0, // mov ecx, RECEIVE
+        0xcd, 0x21, // int 0x21
+        0x8b, 0x4d, 0x10, // mov ecx, [ebp+16]    ; status
+        0x89, 0x19, // mov [ecx], ebx
+        e0, e1, e2,
+    ])
+}
+
+/// `int notify(endpoint_t)`
+const fn notify_trampoline() -> Trampoline {
+    let [p0, p1, p2, p3] = PROLOGUE;
+    let [e0, e1, e2] = EPILOGUE;
+    trampoline(&[
+        p0, p1, p2, p3, //
+        0x8b, 0x45, 0x08, // mov eax, [ebp+8]     ; destination
+        0xb9, 0x04, 0, 0, 0, // mov ecx, NOTIFY
+        0xcd, 0x21, // int 0x21
+        e0, e1, e2,
+    ])
+}
+
+/// `int do_kernel_call(message *)`
+const fn kernel_call_trampoline() -> Trampoline {
+    let [p0, p1, p2, p3] = PROLOGUE;
+    let [e0, e1, e2] = EPILOGUE;
+    trampoline(&[
+        p0, p1, p2, p3, //
+        0x8b, 0x45, 0x08, // mov eax, [ebp+8]     ; message
+        0xcd, 0x20, // int 0x20
+        e0, e1, e2,
+    ])
+}
+
+/// `int senda(asynmsg_t *, size_t)`
+const fn senda_trampoline() -> Trampoline {
+    let [p0, p1, p2, p3] = PROLOGUE;
+    let [e0, e1, e2] = EPILOGUE;
+    trampoline(&[
+        p0, p1, p2, p3, //
+        0x8b, 0x45, 0x0c, // mov eax, [ebp+12]    ; table size
+        0x8b, 0x5d, 0x08, // mov ebx, [ebp+8]     ; table
+        0xb9, 0x10, 0, 0, 0, // mov ecx, SENDA
+        0xcd, 0x21, // int 0x21
+        e0, e1, e2,
+    ])
+}
+
+/// the code of the ipc functions, mapped into the processes
+#[repr(C)]
+pub struct UsermappedIpc {
+    send: Trampoline,
+    receive: Trampoline,
+    sendrec: Trampoline,
+    sendnb: Trampoline,
+    notify: Trampoline,
+    do_kernel_call: Trampoline,
+    senda: Trampoline,
+}
+
+impl Default for UsermappedIpc {
+    fn default() -> Self {
+        Self {
+            send: ipc_trampoline(1),
+            receive: receive_trampoline(),
+            sendrec: ipc_trampoline(3),
+            sendnb: ipc_trampoline(5),
+            notify: notify_trampoline(),
+            do_kernel_call: kernel_call_trampoline(),
+            senda: senda_trampoline(),
+        }
+    }
+}
+
+#[repr(C)]
+pub struct Machine {
+    pub processors_count: u32, // how many cpus are available
+    pub bsp_id: u32,           // id of the bootstrap cpu
+    pub padmode: i32,          // floppy related stuff
+    pub apic_enabled: i32,     // does the kernel use APIC or not?
+    pub acpi_rsdp: u32,        // where is the acpi RSDP
+    pub board_id: u32,         // identifier for the board
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self {
+            processors_count: 1,
+            bsp_id: 0,
+            padmode: 0,
+            apic_enabled: 0,
+            acpi_rsdp: 0,
+            board_id: 0,
+        }
+    }
+}
+
+const LOAD_HISTORY: usize = 48; // history of the load average, in `_LOAD_UNIT_SECS` units
+
+#[repr(C)]
+pub struct Loadinfo {
+    pub proc_load_history: [u16; LOAD_HISTORY], // history of the number of runnable processes
+    pub proc_last_slot: u16,
+    pub last_clock: u32, // : clock_t
+}
+
+impl Default for Loadinfo {
+    fn default() -> Self {
+        Self {
+            proc_load_history: [0; LOAD_HISTORY],
+            proc_last_slot: 0,
+            last_clock: 0,
+        }
+    }
+}
+
+const KMESS_BUF_SIZE: usize = 10000;
+
+/// the kernel messages buffer, read by the `dmesg`-like services
+#[repr(C)]
+pub struct Kmessages {
+    pub km_next: i32,                         // next index to write
+    pub km_size: i32,                         // current size in buffer
+    pub km_buf: [u8; KMESS_BUF_SIZE],         // buffer for messages
+    pub kmess_buf: [u8; 80 * KMESS_BUF_SIZE], // circular buffer for messages
+    pub blpos: i32,                           // kmess_buf position
+}
+
+/// this type is defined in `include/minix/ipc.h`
+#[repr(C)]
+pub struct MinixKuserinfo {
+    pub kui_size: u32,    // size of this structure
+    pub kui_user_sp: u32, // initial user stack pointer
+}
+
+impl Default for MinixKuserinfo {
+    fn default() -> Self {
+        Self {
+            kui_size: size_of::<Self>() as u32,
+            kui_user_sp: crate::sys::USER_SP_TOP,
+        }
+    }
+}
+
 pub const SHARED_BASE_ADDR: u32 = 0xf1002000;
 
+// The kernel messages buffer is too big to keep in memory with the rest
+// of the image, so it's placed right after it in the shared memory,
+// where it's zeroed (meaning empty) on creation
+const KMESSAGES_OFFSET: usize = size_of::<SharedImage>();
+const_assert_eq!(KMESSAGES_OFFSET % std::mem::align_of::<Kmessages>(), 0);
+
+/// the size of the usermapped memory
+pub const USERMAPPED_SIZE: usize = KMESSAGES_OFFSET + size_of::<Kmessages>();
+
 #[repr(C)]
 pub struct SharedImage {
     pub minix_kerninfo: MinixKerninfo,
     pub kclockinfo: Clockinfo,
+    pub minix_ipcvecs: MinixIpcvecs,
+    pub usermapped_ipc: UsermappedIpc,
+    pub kinfo: KInfo,
+    pub machine: Machine,
+    pub loadinfo: Loadinfo,
+    pub kuserinfo: MinixKuserinfo,
+}
+
+impl SharedImage {
+    /// the address of a field of the image, as mapped in the processes
+    fn mapped_addr<T>(&self, field: &T) -> u32 {
+        let offset = field as *const T as usize - self as *const Self as usize;
+        SHARED_BASE_ADDR + offset as u32
+    }
 }
 
 impl Default for SharedImage {
@@ -81,10 +323,72 @@ impl Default for SharedImage {
         let mut image = Self {
             minix_kerninfo: MinixKerninfo::default(),
             kclockinfo: Clockinfo::default(),
+            minix_ipcvecs: MinixIpcvecs::default(),
+            usermapped_ipc: UsermappedIpc::default(),
+            kinfo: KInfo::new(NR_PROCS, SHARED_BASE_ADDR + KMESSAGES_OFFSET as u32),
+            machine: Machine::default(),
+            loadinfo: Loadinfo::default(),
+            kuserinfo: MinixKuserinfo::default(),
         };
 
-        image.minix_kerninfo.kclockinfo_ptr = SHARED_BASE_ADDR + size_of::<MinixKerninfo>() as u32;
+        image.minix_kerninfo.kclockinfo_ptr = image.mapped_addr(&image.kclockinfo);
+
+        // point the ipcvecs at the trampolines
+        let code = &image.usermapped_ipc;
+        image.minix_ipcvecs = MinixIpcvecs {
+            send: image.mapped_addr(&code.send),
+            receive: image.mapped_addr(&code.receive),
+            sendrec: image.mapped_addr(&code.sendrec),
+            sendnb: image.mapped_addr(&code.sendnb),
+            notify: image.mapped_addr(&code.notify),
+            do_kernel_call: image.mapped_addr(&code.do_kernel_call),
+            senda: image.mapped_addr(&code.senda),
+        };
+        image.minix_kerninfo.minix_ipcvecs_ptr = image.mapped_addr(&image.minix_ipcvecs);
+        image.minix_kerninfo.ki_flags |= ki_flags::MINIX_KIF_IPCVECS;
+
+        let kerninfo = &image.minix_kerninfo;
+        image.minix_kerninfo = MinixKerninfo {
+            kinfo_ptr: image.mapped_addr(&image.kinfo),
+            machine_ptr: image.mapped_addr(&image.machine),
+            kmessages_ptr: image.kinfo.kmessages,
+            loadinfo_ptr: image.mapped_addr(&image.loadinfo),
+            kuserinfo_ptr: image.mapped_addr(&image.kuserinfo),
+            ki_flags: kerninfo.ki_flags | ki_flags::MINIX_KIF_USERINFO,
+            ..*kerninfo
+        };
 
         image
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_image_test() {
+        let image = SharedImage::default();
+        let kerninfo = &image.minix_kerninfo;
+
+        // all the structures are inside the usermapped memory
+        let end = SHARED_BASE_ADDR + USERMAPPED_SIZE as u32;
+        for &(ptr, size) in &[
+            (kerninfo.kinfo_ptr, size_of::<KInfo>()),
+            (kerninfo.machine_ptr, size_of::<Machine>()),
+            (kerninfo.kmessages_ptr, size_of::<Kmessages>()),
+            (kerninfo.loadinfo_ptr, size_of::<Loadinfo>()),
+            (kerninfo.minix_ipcvecs_ptr, size_of::<MinixIpcvecs>()),
+            (kerninfo.kuserinfo_ptr, size_of::<MinixKuserinfo>()),
+            (kerninfo.kclockinfo_ptr, size_of::<Clockinfo>()),
+        ] {
+            assert!(ptr >= SHARED_BASE_ADDR && ptr + size as u32 <= end);
+        }
+
+        assert_eq!(
+            kerninfo.ki_flags,
+            ki_flags::MINIX_KIF_IPCVECS | ki_flags::MINIX_KIF_USERINFO
+        );
+        assert_eq!(image.kinfo.kmessages, kerninfo.kmessages_ptr);
+    }
+}
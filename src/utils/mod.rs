@@ -1,3 +1,5 @@
+mod child_waiter;
+mod clock;
 mod message;
 mod message_queue;
 mod minix_process;
@@ -8,6 +10,8 @@ pub mod minix_errno;
 mod minix_shared;
 mod shared_memory;
 
+pub use child_waiter::*;
+pub use clock::*;
 pub use endpoint::Endpoint;
 pub use message::*;
 pub use minix_process::*;
@@ -55,6 +59,28 @@ pub mod endpoint {
     pub const LAST_SPECIAL_PROC_NR: Endpoint = 11;
     pub const INIT_PROC_NR: Endpoint = LAST_SPECIAL_PROC_NR;
     pub const NR_BOOT_MODULES: Endpoint = INIT_PROC_NR + 1;
+
+    /// checks if the process number belongs to a kernel task
+    pub const fn iskerneln(proc_nr: Endpoint) -> bool {
+        proc_nr < 0
+    }
+
+    pub const ENDPOINT_MAX_GENERATION: Endpoint = Endpoint::MAX / ENDPOINT_GENERATION_SIZE - 1;
+
+    // generation number and process number conversions,
+    // as defined by the _ENDPOINT macros in minix/endpoint.h.
+    // The endpoints come from the processes, so they may be any value
+    pub const fn make_endpoint(generation: Endpoint, proc_nr: Endpoint) -> Endpoint {
+        (generation << ENDPOINT_GENERATION_SHIFT) + proc_nr
+    }
+
+    pub const fn endpoint_p(endpoint: Endpoint) -> Endpoint {
+        (endpoint.wrapping_add(MAX_NR_TASKS) % ENDPOINT_GENERATION_SIZE) - MAX_NR_TASKS
+    }
+
+    pub const fn endpoint_g(endpoint: Endpoint) -> Endpoint {
+        endpoint.wrapping_add(MAX_NR_TASKS) / ENDPOINT_GENERATION_SIZE
+    }
 }
 
 pub fn as_buf_u8<T, const N: usize>(val: &T) -> [u8; N] {
@@ -62,3 +88,28 @@ pub fn as_buf_u8<T, const N: usize>(val: &T) -> [u8; N] {
     assert_eq!(N, size_of::<T>());
     unsafe { std::mem::transmute_copy(val) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::endpoint::*;
+
+    #[test]
+    fn endpoint_conversion_test() {
+        // kernel tasks and boot processes have generation 0
+        for &proc_nr in &[ASYNCM, CLOCK, SYSTEM, KERNEL, PM_PROC_NR, INIT_PROC_NR] {
+            let endpoint = make_endpoint(0, proc_nr);
+            assert_eq!(endpoint, proc_nr);
+            assert_eq!(endpoint_p(endpoint), proc_nr);
+            assert_eq!(endpoint_g(endpoint), 0);
+        }
+
+        let endpoint = make_endpoint(3, 42);
+        assert_eq!(endpoint_p(endpoint), 42);
+        assert_eq!(endpoint_g(endpoint), 3);
+
+        // an out of range endpoint doesn't overflow, and
+        // converts to an invalid process number
+        assert!(endpoint_p(Endpoint::MAX) < -MAX_NR_TASKS);
+        assert!(endpoint_g(Endpoint::MAX) < 0);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{ffi::CString, os::raw::c_int};
+use std::{ffi::CString, fs::File, os::raw::c_int, os::unix::io::FromRawFd};
 
 use nix::{
     fcntl::{self, fcntl, FdFlag, OFlag},
@@ -51,6 +51,16 @@ impl SharedMemory {
     }
 }
 
+impl SharedMemory {
+    /// returns a new handle to the shared memory, which isn't inherited
+    /// by the child processes. Writing to it with `write_at` doesn't use
+    /// the file offset `write` relies on, so it can be used from another thread
+    pub fn try_clone_file(&self) -> Result<File, nix::Error> {
+        let fd = fcntl(self.fd, fcntl::F_DUPFD_CLOEXEC(0))?;
+        Ok(unsafe { File::from_raw_fd(fd) })
+    }
+}
+
 impl Drop for SharedMemory {
     fn drop(&mut self) {
         let _ = shm_unlink(self.name.as_c_str());
@@ -0,0 +1,35 @@
+section .data
+msg:                ; kernel call message
+dd 0                ; source
+dd 0x618            ; kernel call number (SYS_SETALARM)
+dd 167772           ; expiration time (10ms at 16 MHz)
+dd 0                ; time left on the previous alarm
+dd 0                ; uptime
+dd 0                ; relative time
+times 40 db 0       ; padding
+
+section .bss
+reply: resb 64      ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, msg        ; message address
+int 0x20            ; do kernel call
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [msg + 4], 0
+jne exit
+mov ebx, 2          ; exit code = 2 if an alarm was already set
+cmp dword [msg + 12], -1
+jne exit
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the alarm
+cmp ebx, 0x21       ; ipc status: NOTIFY, from the kernel
+mov ebx, 3          ; exit code = 3 if the ipc status is wrong
+jne exit
+mov ebx, [reply]    ; exit code = source of the notification
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
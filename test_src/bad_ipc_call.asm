@@ -0,0 +1,17 @@
+section .bss
+msg: resb 64    ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 0      ; destination endpoint
+mov ebx, msg    ; message address
+mov ecx, 7      ; invalid ipc call number
+int 0x21        ; do ipc call
+test ebx, ebx   ; the ipc status should be cleared
+mov ebx, 1      ; exit code = 1 if it isn't
+jnz exit
+mov ebx, eax    ; exit code = result of the ipc call
+exit:
+mov eax, 1      ; system call number (sys_exit)
+int 0x80        ; do system call
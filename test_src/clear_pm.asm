@@ -0,0 +1,39 @@
+section .data
+clear:              ; SYS_CLEAR message
+dd 0                ; source
+dd 0x602            ; kernel call number (SYS_CLEAR)
+dd 42               ; endpoint of the exiting process
+times 52 db 0       ; padding
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; 42 now waits for the reply
+mov eax, clear      ; message address
+int 0x20            ; clear the process
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [clear + 4], 0
+jne exit
+mov dword [clear + 4], 0x602
+mov eax, clear      ; message address
+int 0x20            ; clear the process again
+mov ebx, 2          ; exit code = 2 if it didn't fail with EINVAL
+cmp dword [clear + 4], -22
+jne exit
+mov eax, 42         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; reply to the cleared process
+mov ebx, 3          ; exit code = 3 if it didn't fail with EDEADSRCDST
+cmp eax, -105
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
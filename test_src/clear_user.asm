@@ -0,0 +1,13 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 0          ; destination endpoint (PM)
+mov ebx, msg        ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; wait for the reply, which never comes
+mov ebx, 1          ; exit code = 1 if the sendrec returned
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
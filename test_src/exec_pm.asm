@@ -0,0 +1,37 @@
+section .data
+exec:               ; SYS_EXEC message
+dd 0                ; source
+dd 0x601            ; kernel call number (SYS_EXEC)
+dd 42               ; endpoint of the process
+dd 0                ; stack pointer (filled in)
+dd name             ; address of the command name
+dd 0                ; instruction pointer (filled in)
+dd 0x1234           ; address of the ps_strings struct
+times 36 db 0       ; padding
+
+name: db "exec_new", 0 ; the new command name
+times 16 db 0       ; padding
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the exec request
+mov eax, [msg + 8]  ; the new instruction pointer, from the request
+mov [exec + 20], eax
+mov eax, [msg + 12] ; the new stack pointer, from the request
+mov [exec + 12], eax
+mov eax, exec       ; message address
+int 0x20            ; exec the process, without replying
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [exec + 4], 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,46 @@
+section .data
+request:            ; exec request sent to PM
+dd 0                ; source
+dd 0                ; message type
+dd new_start        ; the new instruction pointer
+dd stack_top        ; the new stack pointer
+times 48 db 0       ; padding
+
+section .bss
+stack: resb 256     ; the new stack
+stack_top:
+
+section .text
+global _start
+_start:
+mov eax, 0          ; destination endpoint (PM)
+mov ebx, request    ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; ask PM for the exec
+mov ebx, 1          ; exit code = 1 if the sendrec returned
+jmp exit
+
+new_start:
+cmp ebx, 0x1234     ; ebx points to the ps_strings struct
+mov ebx, 2          ; exit code = 2 if ebx wasn't set
+jne exit
+mov ebx, 3          ; exit code = 3 if the stack pointer wasn't set
+cmp esp, stack_top
+jne exit
+mov ebx, 4          ; exit code = 4 if the other registers weren't cleared
+cmp eax, 0
+jne exit
+cmp ecx, 0
+jne exit
+cmp edx, 0
+jne exit
+cmp esi, 0
+jne exit
+cmp edi, 0
+jne exit
+cmp ebp, 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
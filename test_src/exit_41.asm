@@ -0,0 +1,21 @@
+section .data
+exit_msg:           ; SYS_EXIT message
+dd 0                ; source
+dd 0x635            ; kernel call number (SYS_EXIT)
+times 56 db 0       ; padding
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; 42 now waits for the reply
+mov eax, exit_msg   ; message address
+int 0x20            ; exit, without replying
+mov ebx, 1          ; exit code = 1 if the kernel call returned
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
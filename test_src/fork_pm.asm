@@ -0,0 +1,43 @@
+section .data
+fork:               ; SYS_FORK message
+dd 0                ; source
+dd 0x600            ; kernel call number (SYS_FORK)
+dd 0                ; endpoint of the parent (filled in)
+dd 43               ; slot of the child
+dd 0                ; flags
+dd 0                ; message address
+times 40 db 0       ; padding
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the fork request
+mov eax, [msg]      ; the parent is the sender of the request
+mov [fork + 8], eax
+mov eax, fork       ; message address
+int 0x20            ; fork the parent
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [fork + 4], 0
+jne exit
+mov dword [msg + 4], 0 ; reply type = OK
+mov eax, [fork + 8] ; the child's endpoint, from the reply
+mov [msg + 8], eax
+mov eax, [msg]      ; destination endpoint (the parent)
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; reply to the parent with the child's endpoint
+mov dword [msg + 8], 0
+mov eax, [fork + 8] ; destination endpoint (the child)
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; reply to the child with 0
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,23 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; do ipc call, which isn't allowed
+mov ebx, 1          ; exit code = 1 if the error isn't ECALLDENIED
+cmp eax, -104
+jne exit
+mov eax, 42         ; destination endpoint
+mov ecx, 4          ; ipc call number (NOTIFY)
+int 0x21            ; do ipc call, which isn't allowed
+mov ebx, 2          ; exit code = 2 if the error isn't ECALLDENIED
+cmp eax, -104
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
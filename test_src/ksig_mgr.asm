@@ -0,0 +1,82 @@
+section .data
+kill:               ; SYS_KILL message
+dd 0                ; source
+dd 0x606            ; kernel call number (SYS_KILL)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process
+dd 15               ; signal number (SIGTERM)
+times 32 db 0       ; padding
+
+badkill:            ; SYS_KILL message, with an invalid signal
+dd 0                ; source
+dd 0x606            ; kernel call number (SYS_KILL)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process
+dd 0                ; signal number (invalid)
+times 32 db 0       ; padding
+
+getksig:            ; SYS_GETKSIG message
+dd 0                ; source
+dd 0x607            ; kernel call number (SYS_GETKSIG)
+times 56 db 0       ; signal map, endpoint and padding
+
+endksig:            ; SYS_ENDKSIG message
+dd 0                ; source
+dd 0x608            ; kernel call number (SYS_ENDKSIG)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process
+times 36 db 0       ; padding
+
+section .bss
+reply: resb 64      ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, badkill    ; message address
+int 0x20            ; signal the process with signal 0
+mov ebx, 8          ; exit code = 8 if it didn't fail with EINVAL
+cmp dword [badkill + 4], -22
+jne exit
+mov eax, kill       ; message address
+int 0x20            ; signal the process
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [kill + 4], 0
+jne exit
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the notification
+mov ebx, 2          ; exit code = 2 if it's not from SYSTEM
+cmp dword [reply], -2
+jne exit
+mov ebx, 3          ; exit code = 3 if SIGKSIG isn't pending
+test dword [reply + 32], 0x200
+jz exit
+mov eax, 42         ; destination endpoint
+mov ecx, 4          ; ipc call number (NOTIFY)
+int 0x21            ; notify the signaled process
+mov eax, getksig    ; message address
+int 0x20            ; get the pending signals
+mov ebx, 4          ; exit code = 4 if the kernel call failed
+cmp dword [getksig + 4], 0
+jne exit
+mov ebx, 5          ; exit code = 5 if it's the wrong process
+cmp dword [getksig + 24], 42
+jne exit
+mov ebx, 6          ; exit code = 6 if SIGTERM isn't pending
+test dword [getksig + 8], 0x4000
+jz exit
+mov eax, endksig    ; message address
+int 0x20            ; let the process resume
+mov ebx, 7          ; exit code = 7 if the kernel call failed
+cmp dword [endksig + 4], 0
+jne exit
+mov eax, 42         ; source endpoint
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the resumed process
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,17 @@
+section .bss
+reply: resb 64      ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the notification
+mov eax, [reply]    ; destination endpoint (the notification's source)
+mov ebx, reply      ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; reply to the signal manager
+mov ebx, [reply]    ; exit code = source of the notification
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
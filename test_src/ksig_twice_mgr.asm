@@ -0,0 +1,69 @@
+section .data
+kill:               ; SYS_KILL message
+dd 0                ; source
+dd 0x606            ; kernel call number (SYS_KILL)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process
+dd 15               ; signal number (SIGTERM)
+times 32 db 0       ; padding
+
+kill2:              ; second SYS_KILL message
+dd 0                ; source
+dd 0x606            ; kernel call number (SYS_KILL)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process
+dd 1                ; signal number (SIGHUP)
+times 32 db 0       ; padding
+
+getksig:            ; SYS_GETKSIG message
+dd 0                ; source
+dd 0x607            ; kernel call number (SYS_GETKSIG)
+times 56 db 0       ; signal map, endpoint and padding
+
+endksig:            ; SYS_ENDKSIG message
+dd 0                ; source
+dd 0x608            ; kernel call number (SYS_ENDKSIG)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process
+times 36 db 0       ; padding
+
+section .bss
+reply: resb 64      ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, kill       ; message address
+int 0x20            ; signal the running process
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [kill + 4], 0
+jne exit
+mov eax, kill2      ; message address
+int 0x20            ; signal the process again, while it's held
+mov ebx, 2          ; exit code = 2 if the kernel call failed
+cmp dword [kill2 + 4], 0
+jne exit
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the notification
+mov ebx, 3          ; exit code = 3 if it's not from SYSTEM
+cmp dword [reply], -2
+jne exit
+mov eax, getksig    ; message address
+int 0x20            ; get the pending signals
+mov ebx, 4          ; exit code = 4 if the kernel call failed
+cmp dword [getksig + 4], 0
+jne exit
+mov ebx, 5          ; exit code = 5 if not both signals are pending
+cmp dword [getksig + 8], 0x4001
+jne exit
+mov eax, endksig    ; message address
+int 0x20            ; finish the signal handling
+mov ebx, 6          ; exit code = 6 if the kernel call failed
+cmp dword [endksig + 4], 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
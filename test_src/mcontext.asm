@@ -0,0 +1,56 @@
+section .data
+getmcontext:        ; SYS_GETMCONTEXT message
+dd 0                ; source
+dd 0x632            ; kernel call number (SYS_GETMCONTEXT)
+dd 42               ; endpoint of the process (itself)
+dd mc               ; address of the machine context
+times 48 db 0       ; padding
+
+setmcontext:        ; SYS_SETMCONTEXT message
+dd 0                ; source
+dd 0x633            ; kernel call number (SYS_SETMCONTEXT)
+dd 42               ; endpoint of the process (itself)
+dd mc               ; address of the machine context
+times 48 db 0       ; padding
+
+section .bss
+mc: resb 732        ; mcontext_t
+
+section .text
+global _start
+_start:
+mov esi, 0x1234     ; saved in the general registers
+mov eax, 0x11223344
+movd xmm0, eax      ; saved in the FPU state
+mov eax, getmcontext ; message address
+int 0x20            ; get the machine context
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [getmcontext + 4], 0
+jne exit
+mov ebx, 2          ; exit code = 2 if esi wasn't saved
+cmp dword [mc + 20], 0x1234
+jne exit
+mov ebx, 3          ; exit code = 3 if the FPU state wasn't saved
+test dword [mc + 724], 1
+jz exit
+cmp dword [mc + 236], 0x11223344
+jne exit
+pxor xmm0, xmm0     ; clobber the FPU state
+mov dword [mc + 56], resumed ; continue at `resumed`
+mov dword [mc + 44], 0x77    ; with eax = 0x77
+mov eax, setmcontext ; message address
+int 0x20            ; set the machine context
+mov ebx, 4          ; exit code = 4 if the context wasn't set
+jmp exit
+resumed:
+mov ebx, 5          ; exit code = 5 if eax wasn't restored
+cmp eax, 0x77
+jne exit
+mov ebx, 6          ; exit code = 6 if the FPU state wasn't restored
+movd eax, xmm0
+cmp eax, 0x11223344
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,42 @@
+section .data
+table:              ; asynchronous message table with one entry
+dd 9                ; flags (AMF_VALID | AMF_NOREPLY)
+dd 42               ; destination endpoint
+dd -1               ; result
+dd 0                ; message source
+dd 8                ; message type
+times 56 db 0       ; message payload
+reply:              ; reply message
+dd 0                ; source
+dd 7                ; message type
+times 56 db 0       ; padding
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; 42 now waits for the reply
+mov eax, 1          ; number of table entries
+mov ebx, table      ; table address
+mov ecx, 16         ; ipc call number (SENDA)
+int 0x21            ; send a message which isn't the reply
+mov ebx, 1          ; exit code = 1 if the senda failed
+cmp eax, 0
+jne exit
+mov eax, 42         ; destination endpoint
+mov ebx, reply      ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; send the reply
+mov eax, 42         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait until 42 got both messages
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,28 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 41         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; do ipc call
+mov ebx, 1          ; exit code = 1 if the reply isn't received first
+cmp dword [msg + 4], 7
+jne exit
+mov eax, 41         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; get the asynchronous message
+mov ebx, 2          ; exit code = 2 if it's not the asynchronous message
+cmp dword [msg + 4], 8
+jne exit
+mov eax, 41         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; let 41 exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
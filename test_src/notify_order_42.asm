@@ -0,0 +1,29 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 43         ; source endpoint (the other notifier)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait until the other notifier notified the receiver
+mov eax, 44         ; destination endpoint (the receiver)
+mov ebx, msg        ; message address
+mov ecx, 4          ; ipc call number (NOTIFY)
+int 0x21            ; notify the receiver second
+mov eax, 45         ; source endpoint (the user process)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; receive the user process's request
+mov eax, 45         ; destination endpoint (the user process)
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; reply to the user process
+mov eax, 44         ; source endpoint (the receiver)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; stay alive until the notification is received
+mov ebx, 0          ; exit code = 0
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,40 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 45         ; source endpoint (the user process)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait until both notifications are pending
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; receive the first notification
+mov ebx, 1          ; exit code = 1 if it isn't from 42, the lower privilege id
+cmp dword [msg], 42
+jne exit
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; receive the second notification
+mov ebx, 2          ; exit code = 2 if it isn't from 43
+cmp dword [msg], 43
+jne exit
+mov eax, 45         ; destination endpoint (the user process)
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; reply to the user process
+mov eax, 42         ; destination endpoint (the second notifier)
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; let it exit
+mov eax, 43         ; destination endpoint (the first notifier)
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; let it exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,25 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; destination endpoint (the second notifier)
+mov ebx, msg        ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; wait until both notifications are pending
+mov eax, 44         ; destination endpoint (the receiver)
+mov ebx, msg        ; message address
+mov ecx, 4          ; ipc call number (NOTIFY)
+int 0x21            ; user processes may not notify
+mov ebx, 1          ; exit code = 1 if the notification wasn't denied
+cmp eax, -110       ; ETRAPDENIED
+jne exit
+mov eax, 44         ; destination endpoint (the receiver)
+mov ebx, msg        ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; let the receiver receive the notifications
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
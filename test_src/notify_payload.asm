@@ -0,0 +1,36 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; get the notification from SYSTEM
+mov ebx, 1          ; exit code = 1 if it's not from SYSTEM
+cmp dword [msg], -2
+jne exit
+mov ebx, 2          ; exit code = 2 if SIGKMESS and SIGKSIG aren't pending
+cmp dword [msg + 24], 0
+jne exit
+cmp dword [msg + 28], 0
+jne exit
+cmp dword [msg + 32], 0x280
+jne exit
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; get the notification from HARDWARE
+mov ebx, 3          ; exit code = 3 if it's not from HARDWARE
+cmp dword [msg], -1
+jne exit
+mov ebx, 4          ; exit code = 4 if the interrupts 0 and 2 aren't pending
+cmp dword [msg + 16], 5
+jne exit
+cmp dword [msg + 20], 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,40 @@
+section .data
+reply:              ; reply message
+dd 0                ; source
+dd 7                ; message type
+times 56 db 0       ; padding
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; 42 now waits for the reply
+mov eax, 42         ; destination endpoint
+mov ecx, 4          ; ipc call number (NOTIFY)
+int 0x21            ; notify 42, before replying
+mov ebx, 1          ; exit code = 1 if the notify failed
+cmp eax, 0
+jne exit
+mov eax, 42         ; destination endpoint
+mov ebx, reply      ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; reply to 42
+mov ebx, 2          ; exit code = 2 if the send failed
+cmp eax, 0
+jne exit
+mov eax, 42         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; stay alive until 42 got the notification
+mov ebx, 3          ; exit code = 3 if the receive failed
+cmp eax, 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
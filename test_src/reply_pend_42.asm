@@ -0,0 +1,37 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 41         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; wait for the reply
+mov ebx, 1          ; exit code = 1 if it's not the reply
+cmp dword [msg + 4], 7
+jne exit
+mov eax, 41         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; get the notification, still pending
+mov ecx, ebx        ; the ipc status
+mov ebx, 2          ; exit code = 2 if it's not a notification
+cmp ecx, 0x20
+jne exit
+cmp dword [msg + 4], 0x1000
+jne exit
+mov ebx, 3          ; exit code = 3 if it's not from 41
+cmp dword [msg], 41
+jne exit
+mov eax, 41         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; let 41 exit
+mov ebx, 4          ; exit code = 4 if the send failed
+cmp eax, 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,26 @@
+section .data
+msg: dd 0, 0        ; message source and type
+db 42               ; first byte of the payload
+times 55 db 0
+exit_msg: dd 0, 1   ; message to PM, of type PM_EXIT
+dd 0                ; exit status
+times 52 db 0
+
+section .text
+global _start
+_start:
+mov eax, 42         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; do ipc call
+mov ebx, 1          ; exit code = 1 if the send failed
+cmp eax, 0
+jne exit
+mov eax, 0          ; destination endpoint (PM)
+mov ebx, exit_msg   ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; exit like the Minix libc, PM isn't there
+mov ebx, eax        ; exit code = result of the ipc call
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,13 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; destination endpoint, which isn't there
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; do ipc call
+mov ebx, eax        ; exit code = result of the ipc call
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
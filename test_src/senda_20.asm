@@ -0,0 +1,30 @@
+section .data
+table:              ; asynchronous message table with one entry
+dd 5                ; flags (AMF_VALID | AMF_NOTIFY)
+dd 21               ; destination endpoint
+dd -1               ; result
+dd 0                ; message source
+dd 7                ; message type
+times 56 db 0       ; message payload
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 1          ; number of table entries
+mov ebx, table      ; table address
+mov ecx, 16         ; ipc call number (SENDA)
+int 0x21            ; do ipc call
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the ASYNCM notification
+mov ebx, 0xff       ; exit code = 0xff if the source isn't ASYNCM
+cmp dword [msg], -5 ; source of the received message
+jne exit
+mov ebx, [table]    ; exit code = flags of the table entry
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,13 @@
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 20         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; do ipc call
+mov ebx, [msg + 4]  ; exit code = type of the received message
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,60 @@
+section .data
+bad_table:          ; asynchronous message table with a bad entry
+dd 0x41             ; flags (AMF_VALID, and an invalid bit)
+dd 21               ; destination endpoint
+dd -1               ; result
+times 64 db 0       ; message
+
+table:              ; asynchronous message table with two entries
+dd 5                ; flags (AMF_VALID | AMF_NOTIFY)
+dd 21               ; destination endpoint
+dd -1               ; result
+dd 0                ; message source
+dd 7                ; message type
+times 56 db 0       ; message payload
+
+dd 1                ; flags (AMF_VALID)
+dd 99               ; destination endpoint (not alive)
+dd -1               ; result
+times 64 db 0       ; message
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 1          ; number of table entries
+mov ebx, bad_table  ; table address
+mov ecx, 16         ; ipc call number (SENDA)
+int 0x21            ; do ipc call
+mov ebx, 0xfd       ; exit code = 0xfd if the bad table isn't rejected untouched
+cmp eax, -22        ; EINVAL
+jne exit
+cmp dword [bad_table], 0x41
+jne exit
+cmp dword [bad_table + 8], -1
+jne exit
+mov eax, 2          ; number of table entries
+mov ebx, table      ; table address
+mov ecx, 16         ; ipc call number (SENDA)
+int 0x21            ; do ipc call
+mov ebx, 0xfe       ; exit code = 0xfe if the result of the last entry isn't returned
+cmp eax, -105       ; EDEADSRCDST
+jne exit
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the ASYNCM notification
+mov ebx, 0xff       ; exit code = 0xff if the source isn't ASYNCM
+cmp dword [msg], -5 ; source of the received message
+jne exit
+mov ebx, 0xfc       ; exit code = 0xfc if the last entry isn't done with EDEADSRCDST
+cmp dword [table + 76], 3
+jne exit
+cmp dword [table + 84], -105
+jne exit
+mov ebx, [table]    ; exit code = flags of the delivered entry
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,36 @@
+section .data
+exit_msg: dd 0, 1   ; message to PM, of type PM_EXIT
+dd 0                ; exit status
+times 52 db 0
+
+section .bss
+msg: resb 64        ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 40         ; source endpoint
+mov ebx, msg        ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; do ipc call
+mov ebx, 1          ; exit code = 1 if the receive failed
+cmp eax, 0
+jne exit
+mov al, [msg+8]     ; second byte = first byte + 1
+inc al
+mov [msg+9], al
+mov eax, 40         ; destination endpoint
+mov ebx, msg        ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; send the message back
+mov ebx, 2          ; exit code = 2 if the send failed
+cmp eax, 0
+jne exit
+mov eax, 0          ; destination endpoint (PM)
+mov ebx, exit_msg   ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; exit like the Minix libc, PM isn't there
+mov ebx, eax        ; exit code = result of the ipc call
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,13 @@
+section .bss
+msg: resb 64    ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 50     ; destination endpoint
+mov ebx, msg    ; message address
+mov ecx, 3      ; ipc call number (SENDREC)
+int 0x21        ; do ipc call
+mov ebx, eax    ; exit code = result of the ipc call
+mov eax, 1      ; system call number (sys_exit)
+int 0x80        ; do system call
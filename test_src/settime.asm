@@ -0,0 +1,75 @@
+section .data
+settime:            ; SYS_SETTIME message, setting the time
+dd 0                ; source
+dd 0x628            ; kernel call number (SYS_SETTIME)
+dd 1                ; set the time now
+dd 0                ; clock id (CLOCK_REALTIME)
+dq 2000000000       ; seconds
+dd 500000000        ; nanoseconds
+times 36 db 0       ; padding
+
+badnsec:            ; SYS_SETTIME message, with invalid nanoseconds
+dd 0                ; source
+dd 0x628            ; kernel call number (SYS_SETTIME)
+dd 1                ; set the time now
+dd 0                ; clock id (CLOCK_REALTIME)
+dq 2000000000       ; seconds
+dd -500000000       ; nanoseconds
+times 36 db 0       ; padding
+
+badclock:           ; SYS_SETTIME message, for a clock that can't be set
+dd 0                ; source
+dd 0x628            ; kernel call number (SYS_SETTIME)
+dd 1                ; set the time now
+dd 1                ; clock id (CLOCK_VIRTUAL)
+dq 0                ; seconds
+dd 0                ; nanoseconds
+times 36 db 0       ; padding
+
+adjtime:            ; SYS_SETTIME message, adjusting the time
+dd 0                ; source
+dd 0x628            ; kernel call number (SYS_SETTIME)
+dd 0                ; adjust the time
+dd 0                ; clock id (CLOCK_REALTIME)
+dq -1               ; seconds
+dd -500000000       ; nanoseconds
+times 36 db 0       ; padding
+
+stime:              ; SYS_STIME message
+dd 0                ; source
+dd 0x627            ; kernel call number (SYS_STIME)
+dq 1000000000       ; boot time
+times 48 db 0       ; padding
+
+section .text
+global _start
+_start:
+mov eax, settime    ; message address
+int 0x20            ; set the time to 2000000000.5
+mov ebx, 1          ; exit code = 1 if the kernel call failed
+cmp dword [settime + 4], 0
+jne exit
+mov eax, badnsec    ; message address
+int 0x20            ; try to set the time with negative nanoseconds
+mov ebx, 5          ; exit code = 5 if it didn't fail with EINVAL
+cmp dword [badnsec + 4], -22
+jne exit
+mov eax, badclock   ; message address
+int 0x20            ; try to set another clock
+mov ebx, 2          ; exit code = 2 if it didn't fail with EINVAL
+cmp dword [badclock + 4], -22
+jne exit
+mov eax, adjtime    ; message address
+int 0x20            ; adjust the time by -1.5 seconds
+mov ebx, 3          ; exit code = 3 if the kernel call failed
+cmp dword [adjtime + 4], 0
+jne exit
+mov eax, stime      ; message address
+int 0x20            ; set the boot time
+mov ebx, 4          ; exit code = 4 if the kernel call failed
+cmp dword [stime + 4], 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
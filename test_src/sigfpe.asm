@@ -0,0 +1,10 @@
+section .text
+global _start
+_start:
+mov eax, 1          ; dividend
+xor edx, edx
+xor ecx, ecx        ; divisor = 0
+div ecx             ; cause a divide error (SIGFPE)
+mov ebx, 1          ; exit code = 1 if the process was resumed
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
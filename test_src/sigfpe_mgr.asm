@@ -0,0 +1,34 @@
+section .data
+getksig:            ; SYS_GETKSIG message
+dd 0                ; source
+dd 0x607            ; kernel call number (SYS_GETKSIG)
+times 56 db 0       ; signal map, endpoint and padding
+
+section .bss
+reply: resb 64      ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 0x7c00     ; source endpoint (ANY)
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; wait for the notification
+mov ebx, 1          ; exit code = 1 if it's not from SYSTEM
+cmp dword [reply], -2
+jne exit
+mov eax, getksig    ; message address
+int 0x20            ; get the pending signals
+mov ebx, 2          ; exit code = 2 if the kernel call failed
+cmp dword [getksig + 4], 0
+jne exit
+mov ebx, 3          ; exit code = 3 if it's the wrong process
+cmp dword [getksig + 24], 42
+jne exit
+mov ebx, 4          ; exit code = 4 if SIGFPE isn't pending
+test dword [getksig + 8], 0x80
+jz exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,73 @@
+section .data
+sigsend:            ; SYS_SIGSEND message
+dd 0                ; source
+dd 0x609            ; kernel call number (SYS_SIGSEND)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process (itself)
+dd 0                ; signal number (unused)
+dd sigmsg           ; signal details
+times 28 db 0       ; flags and padding
+
+sigreturn:          ; SYS_SIGRETURN message
+dd 0                ; source
+dd 0x60a            ; kernel call number (SYS_SIGRETURN)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the process returning from the handler
+dd 0                ; signal number (unused)
+dd 0                ; sigcontext address, filled in by the stub
+times 28 db 0       ; flags and padding
+
+sigmsg:             ; signal details
+dd 15               ; signal number (SIGTERM)
+times 16 db 0       ; signal mask
+dd handler          ; address of the signal handler
+dd sigreturn_stub   ; address of the sigreturn stub
+dd 0                ; stack pointer
+
+signum: dd 0        ; the signal number the handler was called with
+handler_xmm0: dd 0  ; the FPU state the handler started with
+
+section .text
+global _start
+_start:
+mov esi, 0x1234     ; should be restored after the handler
+mov eax, 0x5566
+movd xmm0, eax      ; the FPU state should be restored too
+mov eax, sigsend    ; message address
+int 0x20            ; make the handler run
+mov ebx, 1          ; exit code = 1 if the handler didn't run
+cmp dword [signum], 15
+jne exit
+mov ebx, 2          ; exit code = 2 if the registers weren't restored
+cmp esi, 0x1234
+jne exit
+mov ebx, 4          ; exit code = 4 if the handler didn't get a clean FPU
+cmp dword [handler_xmm0], 0
+jne exit
+mov ebx, 5          ; exit code = 5 if the FPU state wasn't restored
+movd eax, xmm0
+cmp eax, 0x5566
+jne exit
+mov ebx, [sigsend + 4] ; exit code = result of the kernel call
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
+
+handler:
+mov eax, [esp + 4]  ; the "signum" argument
+mov [signum], eax
+xor esi, esi        ; clobber the register
+movd eax, xmm0
+mov [handler_xmm0], eax
+mov eax, 0x99
+movd xmm0, eax      ; clobber the FPU state
+ret                 ; return to the sigreturn stub
+
+sigreturn_stub:
+add esp, 16         ; skip the handler arguments and the frame pointer
+mov eax, [esp + 4]  ; the sigcontext address
+mov [sigreturn + 32], eax
+mov eax, sigreturn  ; message address
+int 0x20            ; restore the registers
+mov ebx, 3          ; exit code = 3 if sigreturn returned
+jmp exit
@@ -0,0 +1,52 @@
+section .data
+sigsend:            ; SYS_SIGSEND message
+dd 0                ; source
+dd 0x609            ; kernel call number (SYS_SIGSEND)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the signaled process
+dd 0                ; signal number (unused)
+dd sigmsg           ; signal details
+times 28 db 0       ; flags and padding
+
+sigmsg:             ; signal details
+dd 15               ; signal number (SIGTERM)
+times 16 db 0       ; signal mask
+dd 0                ; address of the signal handler, sent by 42
+dd 0                ; address of the sigreturn stub, sent by 42
+dd 0                ; stack pointer
+
+message: times 64 db 0 ; message sent to 42
+
+section .bss
+reply: resb 64      ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 42         ; source endpoint
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; get the handler addresses. 42 waits for the reply
+mov ebx, 1          ; exit code = 1 if the receive failed
+cmp eax, 0
+jne exit
+mov eax, [reply + 8]
+mov [sigmsg + 20], eax
+mov eax, [reply + 12]
+mov [sigmsg + 24], eax
+mov eax, sigsend    ; message address
+int 0x20            ; make 42 run the handler
+mov ebx, 2          ; exit code = 2 if the kernel call failed
+cmp dword [sigsend + 4], 0
+jne exit
+mov eax, 42         ; destination endpoint
+mov ebx, message    ; message address
+mov ecx, 1          ; ipc call number (SEND)
+int 0x21            ; send to 42, after its handler returned
+mov ebx, 3          ; exit code = 3 if the send failed
+cmp eax, 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
@@ -0,0 +1,60 @@
+section .data
+request:            ; message sent to 41
+dd 0                ; source
+dd 0                ; message type
+dd handler          ; address of the signal handler
+dd sigreturn_stub   ; address of the sigreturn stub
+times 48 db 0       ; padding
+
+sigreturn:          ; SYS_SIGRETURN message
+dd 0                ; source
+dd 0x60a            ; kernel call number (SYS_SIGRETURN)
+times 16 db 0       ; signal map
+dd 42               ; endpoint of the process returning from the handler
+dd 0                ; signal number (unused)
+dd 0                ; sigcontext address, filled in by the stub
+times 28 db 0       ; flags and padding
+
+signum: dd 0        ; the signal number the handler was called with
+
+section .bss
+reply: resb 64      ; message buffer
+
+section .text
+global _start
+_start:
+mov eax, 41         ; destination endpoint
+mov ebx, request    ; message address
+mov ecx, 3          ; ipc call number (SENDREC)
+int 0x21            ; wait for a reply, which never comes
+mov ebx, 1          ; exit code = 1 if the handler didn't run
+cmp dword [signum], 15
+jne exit
+mov ebx, 2          ; exit code = 2 if the call wasn't interrupted
+cmp eax, -4
+jne exit
+mov eax, 41         ; source endpoint
+mov ebx, reply      ; message address
+mov ecx, 2          ; ipc call number (RECEIVE)
+int 0x21            ; receive the message sent after the handler returned
+mov ebx, 3          ; exit code = 3 if the receive failed
+cmp eax, 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call
+
+handler:
+mov eax, [esp + 4]  ; the "signum" argument
+mov [signum], eax
+ret                 ; return to the sigreturn stub
+
+sigreturn_stub:
+add esp, 16         ; skip the handler arguments and the frame pointer
+mov eax, [esp + 4]  ; the sigcontext address
+mov [sigreturn + 32], eax
+mov eax, sigreturn  ; message address
+int 0x20            ; restore the registers
+mov ebx, 4          ; exit code = 4 if sigreturn returned
+jmp exit
@@ -0,0 +1,25 @@
+section .data
+times_msg:          ; SYS_TIMES message
+dd 0                ; source
+dd 0x619            ; kernel call number (SYS_TIMES)
+dd 43               ; endpoint of the process, which is gone
+times 52 db 0       ; padding
+
+section .text
+global _start
+_start:
+mov eax, times_msg  ; message address
+int 0x20            ; get the times of the process
+mov ebx, [times_msg + 4] ; exit code = result of the kernel call
+cmp ebx, 0
+jne exit
+mov ebx, 1          ; exit code = 1 if the user time isn't 0
+cmp dword [times_msg + 16], 0
+jne exit
+mov ebx, 2          ; exit code = 2 if the system time isn't 0
+cmp dword [times_msg + 20], 0
+jne exit
+mov ebx, 0          ; exit code = 0 on success
+exit:
+mov eax, 1          ; system call number (sys_exit)
+int 0x80            ; do system call